@@ -1,6 +1,5 @@
 use github::auth::AuthToken;
 use github::client::GitHubClient;
-use tokio;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -27,8 +26,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .create_branch(owner, repo, &new_branch, &base_sha)
                 .await
             {
-                Ok(()) => {
-                    println!("✓ Successfully created branch: {}", new_branch);
+                Ok(git_ref) => {
+                    println!("✓ Successfully created branch: {}", git_ref.ref_name);
                 }
                 Err(e) => {
                     println!("✗ Failed to create branch: {}", e);
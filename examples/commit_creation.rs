@@ -1,4 +1,4 @@
-use github_rs::client::GitHubClient;
+use github::client::GitHubClient;
 
 /// コミット作成のサンプルコード
 #[tokio::main]
@@ -24,23 +24,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .await?;
 
     // 3. ファイル内容のBLOBを作成
-    let blob_sha = client.create_blob(owner, repo, file_content).await?;
+    let blob = client.create_blob(owner, repo, file_content).await?;
 
     // 4. 新しいツリーを作成
-    let new_tree_sha = client
-        .create_tree(owner, repo, &base_tree_sha, file_path, &blob_sha)
+    let new_tree = client
+        .create_tree(owner, repo, &base_tree_sha, file_path, &blob.sha)
         .await?;
 
     // 5. 新しいコミットを作成
-    let new_commit_sha = client
-        .create_commit(owner, repo, commit_message, &new_tree_sha, &base_commit_sha)
+    let new_commit = client
+        .create_commit(owner, repo, commit_message, &new_tree.sha, &base_commit_sha)
         .await?;
 
     // 6. ブランチの先端を更新
     client
-        .update_branch_reference(owner, repo, branch, &new_commit_sha)
+        .update_branch_reference(owner, repo, branch, &new_commit.sha)
         .await?;
 
-    println!("Successfully created commit: {}", new_commit_sha);
+    println!("Successfully created commit: {}", new_commit.sha);
     Ok(())
 }
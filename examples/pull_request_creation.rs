@@ -1,4 +1,4 @@
-use github_rs::client::GitHubClient;
+use github::client::GitHubClient;
 
 /// プルリクエスト作成のサンプルコード
 #[tokio::main]
@@ -17,18 +17,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("プルリクエストを作成中...");
     match client
-        .create_pull_request(
-            owner,
-            repo,
-            base_branch,
-            head_branch,
-            pr_title,
-            pr_body,
-        )
+        .create_pull_request(owner, repo, base_branch, head_branch, pr_title, pr_body)
         .await
     {
-        Ok(()) => {
+        Ok(pr) => {
             println!("✓ プルリクエストの作成に成功しました");
+            println!("  - 番号: #{}", pr.number);
+            println!("  - URL: {}", pr.html_url);
             println!("  - ベースブランチ: {}", base_branch);
             println!("  - ヘッドブランチ: {}", head_branch);
             println!("  - タイトル: {}", pr_title);
@@ -41,7 +36,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         println!("トークンの権限が不足しているか、無効なトークンです");
                     }
                     reqwest::StatusCode::UNPROCESSABLE_ENTITY => {
-                        println!("指定されたブランチが存在しないか、既にプルリクエストが存在します");
+                        println!(
+                            "指定されたブランチが存在しないか、既にプルリクエストが存在します"
+                        );
                     }
                     _ => println!("APIエラー: ステータスコード {}", status),
                 }
@@ -1,6 +1,5 @@
 use github::auth::AuthToken;
 use github::client::GitHubClient;
-use tokio;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -14,12 +13,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test 1: Get user information
     println!("Test 1: Getting user information...");
-    match client.get("/user").await {
-        Ok(response) => {
-            let user_info: serde_json::Value = response.json().await?;
+    match client.get_authenticated_user().await {
+        Ok(user) => {
             println!("✓ Successfully retrieved user info:");
-            println!("  Login: {}", user_info["login"]);
-            println!("  Name: {}", user_info["name"]);
+            println!("  Login: {}", user.login);
+            println!("  Name: {}", user.name.as_deref().unwrap_or("-"));
         }
         Err(e) => {
             println!("✗ Failed to get user info: {}", e);
@@ -34,22 +32,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test 2: List repositories
     println!("\nTest 2: Listing repositories...");
     match client.get_user_repos().await {
-        Ok(response) => {
-            let status = response.status();
-            if status.is_success() {
-                let repos: Vec<serde_json::Value> = response.json().await?;
-                println!("✓ Successfully retrieved repositories:");
-                for repo in repos.iter().take(5) {
-                    println!("  - {} ({})", repo["name"], repo["html_url"]);
-                }
-                if repos.len() > 5 {
-                    println!("  ... and {} more", repos.len() - 5);
-                }
-            } else {
-                println!("✗ Failed to list repos: {}", status);
-                if status == reqwest::StatusCode::FORBIDDEN {
-                    println!("This might be due to invalid token or insufficient permissions");
-                }
+        Ok(repos) => {
+            println!("✓ Successfully retrieved repositories:");
+            for repo in repos.iter().take(5) {
+                println!("  - {} ({})", repo.name, repo.html_url);
+            }
+            if repos.len() > 5 {
+                println!("  ... and {} more", repos.len() - 5);
             }
         }
         Err(e) => {
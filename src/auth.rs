@@ -1,11 +1,67 @@
+pub mod app;
+pub mod credentials;
+pub mod oauth;
+pub mod secret;
+pub mod whoami;
+
+pub use secret::SecretString;
+
+use crate::client::DEFAULT_USER_AGENT;
+use crate::errors::GitHubError;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use reqwest::Client;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// トークン発行などの認証用リクエストのタイムアウト（クライアントを指定しない場合）
+const AUTH_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// `Authorization` ヘッダーのスキーム
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthScheme {
+    /// 従来の `token` プレフィックス（classic PAT / OAuth トークン）
+    #[default]
+    Token,
+    /// `Bearer` プレフィックス（fine-grained PAT、GitHub App のトークン、JWT）
+    Bearer,
+}
+
+impl AuthScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthScheme::Token => "token",
+            AuthScheme::Bearer => "Bearer",
+        }
+    }
+}
 
-#[derive(Clone)]
-pub struct AuthToken(pub String);
+/// API の認証に使うトークン（`Debug` では中身を表示しない）
+#[derive(Clone, Debug)]
+pub struct AuthToken {
+    secret: SecretString,
+    scheme: AuthScheme,
+}
 
 impl AuthToken {
     pub fn new<S: Into<String>>(token: S) -> Self {
-        Self(token.into())
+        Self {
+            secret: SecretString::new(token),
+            scheme: AuthScheme::Token,
+        }
+    }
+
+    /// `Bearer` スキームで送るトークン
+    pub fn bearer<S: Into<String>>(token: S) -> Self {
+        Self::new(token).with_scheme(AuthScheme::Bearer)
+    }
+
+    pub fn with_scheme(mut self, scheme: AuthScheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    pub fn scheme(&self) -> AuthScheme {
+        self.scheme
     }
 
     pub fn from_env() -> Result<Self, std::env::VarError> {
@@ -14,28 +70,48 @@ impl AuthToken {
         dotenvy::dotenv().ok();
         info!(target: "auth", "Loading GitHub token from environment");
         let token = std::env::var("GITHUB_TOKEN")?;
-        debug!(target: "auth", token_prefix = %&token[..10], "Token loaded successfully");
-        Ok(Self(token))
+        debug!(target: "auth", "Token loaded successfully");
+        Ok(Self::new(token))
     }
 
     pub fn as_str(&self) -> &str {
-        &self.0
+        self.secret.expose()
     }
 }
 
-pub fn build_auth_headers(token: &str) -> HeaderMap {
+/// `Authorization` ヘッダーの値を作る
+///
+/// ヘッダーは sensitive として扱われ、reqwest / hyper のデバッグ出力に表示されない。
+pub(crate) fn authorization_header(
+    scheme: AuthScheme,
+    token: &str,
+) -> Result<HeaderValue, GitHubError> {
+    let value = Zeroizing::new(format!("{} {}", scheme.as_str(), token));
+    let mut header = HeaderValue::from_str(&value)
+        .map_err(|_| GitHubError::AuthError("Token contains invalid characters".to_string()))?;
+    header.set_sensitive(true);
+    Ok(header)
+}
+
+/// 認証ヘッダーと `Accept` ヘッダーを作る
+///
+/// ヘッダーに使えない文字を含むトークンは `AuthError` になる。
+pub fn build_auth_headers(token: &str, scheme: AuthScheme) -> Result<HeaderMap, GitHubError> {
     let mut headers = HeaderMap::new();
-    let auth_value = format!("token {}", token); // GitHub API expects "token" prefix
-    println!("Debug - Auth header prefix: token"); // Debug log without exposing full token
-    headers.insert(
-        AUTHORIZATION,
-        HeaderValue::from_str(&auth_value).expect("Invalid token format"),
-    );
+    headers.insert(AUTHORIZATION, authorization_header(scheme, token)?);
     headers.insert(
         ACCEPT,
-        HeaderValue::from_str("application/vnd.github.v3+json").unwrap(),
+        HeaderValue::from_static("application/vnd.github.v3+json"),
     );
-    headers
+    Ok(headers)
+}
+
+/// 認証用リクエストに使う既定の HTTP クライアント
+pub(crate) fn default_http_client() -> Result<Client, GitHubError> {
+    Ok(Client::builder()
+        .user_agent(DEFAULT_USER_AGENT)
+        .timeout(Duration::from_secs(AUTH_REQUEST_TIMEOUT_SECS))
+        .build()?)
 }
 
 #[cfg(test)]
@@ -52,12 +128,35 @@ mod tests {
     #[test]
     fn test_build_auth_headers() {
         let token = "test_token";
-        let headers = build_auth_headers(token);
+        let headers = build_auth_headers(token, AuthScheme::Token).unwrap();
         assert!(headers.contains_key(AUTHORIZATION));
         assert!(headers.contains_key(ACCEPT));
 
         if let Some(auth_value) = headers.get(AUTHORIZATION) {
             assert_eq!(auth_value.to_str().unwrap(), format!("token {}", token));
+            assert!(auth_value.is_sensitive());
         }
     }
+
+    #[test]
+    fn test_build_auth_headers_bearer() {
+        let headers = build_auth_headers("github_pat_test", AuthScheme::Bearer).unwrap();
+        assert_eq!(
+            headers.get(AUTHORIZATION).unwrap().to_str().unwrap(),
+            "Bearer github_pat_test"
+        );
+        assert_eq!(AuthToken::bearer("x").scheme(), AuthScheme::Bearer);
+    }
+
+    #[test]
+    fn test_invalid_token_is_auth_error() {
+        let error = build_auth_headers("bad\ntoken", AuthScheme::Token).unwrap_err();
+        assert!(matches!(error, GitHubError::AuthError(_)));
+    }
+
+    #[test]
+    fn test_auth_token_debug_is_redacted() {
+        let auth = AuthToken::new("ghp_short");
+        assert!(!format!("{:?}", auth).contains("ghp_short"));
+    }
 }
@@ -0,0 +1,616 @@
+use super::credentials::CredentialProvider;
+use super::{default_http_client, AuthToken, SecretString};
+use crate::client::GitHubClientBuilder;
+use crate::errors::GitHubError;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use reqwest::header::ACCEPT;
+use reqwest::{Client, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+/// github.com のウェブ URL（OAuth のエンドポイントは API ではなくこちらにある）
+pub const DEFAULT_WEB_URL: &str = "https://github.com";
+
+/// 期限切れの何分前にユーザートークンを更新するか
+const TOKEN_REFRESH_MARGIN_MINS: i64 = 5;
+
+/// デバイスフローのアクセストークン要求に使う `grant_type`
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// `slow_down` を受けたときにポーリング間隔へ加算する秒数
+const SLOW_DOWN_INCREMENT_SECS: u64 = 5;
+
+/// OAuth / GitHub App のユーザーアクセストークン
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserToken {
+    pub access_token: SecretString,
+    #[serde(default)]
+    pub token_type: Option<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// 期限付きトークンの場合の失効時刻
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub refresh_token: Option<SecretString>,
+    #[serde(default)]
+    pub refresh_token_expires_at: Option<DateTime<Utc>>,
+}
+
+impl UserToken {
+    /// 期限切れ、または期限が近く更新が必要か
+    pub fn needs_refresh(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| {
+            expires_at - Utc::now() < ChronoDuration::minutes(TOKEN_REFRESH_MARGIN_MINS)
+        })
+    }
+}
+
+/// `/login/oauth/access_token` のレスポンス
+///
+/// エラー時も 200 で `error` フィールドが返る。
+#[derive(Debug, Deserialize)]
+pub(crate) struct TokenResponse {
+    #[serde(default)]
+    pub access_token: Option<SecretString>,
+    #[serde(default)]
+    pub token_type: Option<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub expires_in: Option<i64>,
+    #[serde(default)]
+    pub refresh_token: Option<SecretString>,
+    #[serde(default)]
+    pub refresh_token_expires_in: Option<i64>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_description: Option<String>,
+    /// `slow_down` 時に返される新しいポーリング間隔（秒）
+    #[serde(default)]
+    pub interval: Option<u64>,
+}
+
+impl TokenResponse {
+    pub(crate) fn into_user_token(self) -> Result<UserToken, GitHubError> {
+        if let Some(error) = self.error {
+            return Err(oauth_error(error, self.error_description));
+        }
+        let now = Utc::now();
+        Ok(UserToken {
+            access_token: self.access_token.ok_or_else(|| {
+                GitHubError::ParseError("Token response has no access_token".to_string())
+            })?,
+            token_type: self.token_type,
+            scope: self.scope,
+            expires_at: self.expires_in.map(|s| now + ChronoDuration::seconds(s)),
+            refresh_token: self.refresh_token,
+            refresh_token_expires_at: self
+                .refresh_token_expires_in
+                .map(|s| now + ChronoDuration::seconds(s)),
+        })
+    }
+}
+
+/// `/login/device/code` のレスポンス
+///
+/// `user_code` をユーザーに表示し、`verification_uri` で入力してもらう。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    /// `device_code` の有効期間（秒）
+    pub expires_in: u64,
+    /// ポーリングの最小間隔（秒）
+    pub interval: u64,
+}
+
+/// 200 で返されるエラーと通常のレスポンスを区別する
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OAuthResponse<T> {
+    Error {
+        error: String,
+        #[serde(default)]
+        error_description: Option<String>,
+    },
+    Ok(T),
+}
+
+/// OAuth App / GitHub App のクライアント情報
+#[derive(Debug, Clone)]
+pub struct OAuthApp {
+    client_id: String,
+    client_secret: Option<SecretString>,
+    pub(crate) http: Client,
+    pub(crate) web_url: String,
+}
+
+impl OAuthApp {
+    pub fn new(
+        client_id: impl Into<String>,
+        client_secret: Option<String>,
+    ) -> Result<Self, GitHubError> {
+        Ok(Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.map(SecretString::from),
+            http: default_http_client()?,
+            web_url: DEFAULT_WEB_URL.to_string(),
+        })
+    }
+
+    /// トークンのリクエストに使う HTTP クライアントを指定する
+    ///
+    /// GitHub は `User-Agent` のないリクエストを拒否するため、クライアントに設定しておくこと。
+    pub fn with_http_client(mut self, http: Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// `GitHubClientBuilder` のタイムアウト・プロキシ・ルート証明書・ヘッダーを使う
+    pub fn with_client_builder(
+        mut self,
+        builder: &GitHubClientBuilder,
+    ) -> Result<Self, GitHubError> {
+        self.http = builder.http_client()?;
+        Ok(self)
+    }
+
+    /// ウェブ URL を変更する（GitHub Enterprise Server やテスト用サーバー）
+    pub fn with_web_url(mut self, web_url: impl Into<String>) -> Self {
+        self.web_url = web_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// デバイスフローを開始し、ユーザーに表示するコードを取得する
+    pub async fn request_device_code(&self, scopes: &[&str]) -> Result<DeviceCode, GitHubError> {
+        let scope = scopes.join(" ");
+        let mut form = vec![("client_id", self.client_id.as_str())];
+        if !scope.is_empty() {
+            form.push(("scope", scope.as_str()));
+        }
+        match self.post_form("/login/device/code", &form).await? {
+            OAuthResponse::Ok(code) => Ok(code),
+            OAuthResponse::Error {
+                error,
+                error_description,
+            } => Err(oauth_error(error, error_description)),
+        }
+    }
+
+    /// ユーザーが認可するまでポーリングし、ユーザートークンを取得する
+    ///
+    /// `authorization_pending` の間は待機を続け、`slow_down` を受けたら間隔を広げる。
+    /// `expired_token`、`access_denied` などのエラーや `expires_in` の経過で `AuthError` を返す。
+    pub async fn poll_device_token(&self, code: &DeviceCode) -> Result<UserToken, GitHubError> {
+        let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+        let mut interval = Duration::from_secs(code.interval);
+        loop {
+            tokio::time::sleep(interval).await;
+            if Instant::now() >= deadline {
+                return Err(GitHubError::AuthError(
+                    "expired_token: The device code has expired".to_string(),
+                ));
+            }
+            let response = self
+                .post_token(&[
+                    ("grant_type", DEVICE_CODE_GRANT_TYPE),
+                    ("device_code", code.device_code.as_str()),
+                ])
+                .await?;
+            match response.error.as_deref() {
+                Some("authorization_pending") => {
+                    debug!(target: "auth", "Waiting for user to authorize device");
+                }
+                Some("slow_down") => {
+                    interval = response
+                        .interval
+                        .map(Duration::from_secs)
+                        .unwrap_or(interval + Duration::from_secs(SLOW_DOWN_INCREMENT_SECS));
+                    debug!(target: "auth", interval_secs = interval.as_secs(), "Device flow polling slowed down");
+                }
+                _ => return response.into_user_token(),
+            }
+        }
+    }
+
+    /// ウェブフローでユーザーをリダイレクトする認可 URL
+    ///
+    /// `state` はコールバックで照合し、CSRF を防ぐために使う。
+    pub fn authorize_url(
+        &self,
+        redirect_uri: Option<&str>,
+        scopes: &[&str],
+        state: &str,
+    ) -> Result<String, GitHubError> {
+        let mut params = vec![("client_id", self.client_id.clone())];
+        if let Some(redirect_uri) = redirect_uri {
+            params.push(("redirect_uri", redirect_uri.to_string()));
+        }
+        if !scopes.is_empty() {
+            params.push(("scope", scopes.join(" ")));
+        }
+        params.push(("state", state.to_string()));
+        Url::parse_with_params(&format!("{}/login/oauth/authorize", self.web_url), &params)
+            .map(String::from)
+            .map_err(|e| GitHubError::ConfigError(format!("Invalid web URL: {}", e)))
+    }
+
+    /// コールバックで受け取った `code` をユーザートークンに交換する
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        redirect_uri: Option<&str>,
+    ) -> Result<UserToken, GitHubError> {
+        info!(target: "auth", "Exchanging authorization code for user access token");
+        let mut params = vec![("code", code)];
+        if let Some(redirect_uri) = redirect_uri {
+            params.push(("redirect_uri", redirect_uri));
+        }
+        self.post_token(&params).await?.into_user_token()
+    }
+
+    /// リフレッシュトークンで新しいユーザートークンを取得する
+    ///
+    /// GitHub はリフレッシュのたびに新しいリフレッシュトークンを発行するため、
+    /// 返されたトークンを保存し直す必要がある。
+    pub async fn refresh_token(&self, refresh_token: &str) -> Result<UserToken, GitHubError> {
+        info!(target: "auth", "Refreshing user access token");
+        self.post_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ])
+        .await?
+        .into_user_token()
+    }
+
+    /// `/login/oauth/access_token` に `client_id`（と `client_secret`）を付けて送信する
+    pub(crate) async fn post_token(
+        &self,
+        params: &[(&str, &str)],
+    ) -> Result<TokenResponse, GitHubError> {
+        let mut form = vec![("client_id", self.client_id.as_str())];
+        if let Some(secret) = &self.client_secret {
+            form.push(("client_secret", secret.expose()));
+        }
+        form.extend_from_slice(params);
+        self.post_form("/login/oauth/access_token", &form).await
+    }
+
+    pub(crate) async fn post_form<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<T, GitHubError> {
+        let response = self
+            .http
+            .post(format!("{}{}", self.web_url, path))
+            .header(ACCEPT, "application/json")
+            .form(form)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response).await);
+        }
+        Ok(response.json().await?)
+    }
+}
+
+fn oauth_error(error: String, description: Option<String>) -> GitHubError {
+    GitHubError::AuthError(match description {
+        Some(description) => format!("{}: {}", error, description),
+        None => error,
+    })
+}
+
+type RefreshCallback = Box<dyn Fn(&UserToken) + Send + Sync>;
+
+/// 期限付きユーザートークンを自動で更新するプロバイダー
+pub struct OAuthUserCredentials {
+    app: OAuthApp,
+    token: Mutex<UserToken>,
+    on_refresh: Option<RefreshCallback>,
+}
+
+impl OAuthUserCredentials {
+    pub fn new(app: OAuthApp, token: UserToken) -> Self {
+        Self {
+            app,
+            token: Mutex::new(token),
+            on_refresh: None,
+        }
+    }
+
+    /// トークンが更新されたときに呼ばれるコールバック（新しいリフレッシュトークンの保存用）
+    pub fn on_refresh(mut self, callback: impl Fn(&UserToken) + Send + Sync + 'static) -> Self {
+        self.on_refresh = Some(Box::new(callback));
+        self
+    }
+
+    /// 現在保持しているトークン
+    pub async fn current(&self) -> UserToken {
+        self.token.lock().await.clone()
+    }
+
+    async fn refresh(&self, token: &mut UserToken) -> Result<(), GitHubError> {
+        let refresh_token = token.refresh_token.as_ref().ok_or_else(|| {
+            GitHubError::AuthError("User token expired and has no refresh token".to_string())
+        })?;
+        let refreshed = self.app.refresh_token(refresh_token.expose()).await?;
+        if let Some(callback) = &self.on_refresh {
+            callback(&refreshed);
+        }
+        *token = refreshed;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for OAuthUserCredentials {
+    async fn token(&self) -> Result<AuthToken, GitHubError> {
+        let mut token = self.token.lock().await;
+        if token.needs_refresh() {
+            self.refresh(&mut token).await?;
+        }
+        Ok(AuthToken::bearer(token.access_token.expose()))
+    }
+
+    async fn invalidate(&self) -> bool {
+        let mut token = self.token.lock().await;
+        token.refresh_token.is_some() && self.refresh(&mut token).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_expired_token_is_refreshed_and_rotated() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/login/oauth/access_token")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("client_id".into(), "Iv1.abc".into()),
+                Matcher::UrlEncoded("client_secret".into(), "secret".into()),
+                Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+                Matcher::UrlEncoded("refresh_token".into(), "ghr_old".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "access_token": "ghu_new",
+                    "expires_in": 28800,
+                    "refresh_token": "ghr_new",
+                    "refresh_token_expires_in": 15811200,
+                    "token_type": "bearer",
+                    "scope": ""
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let app = OAuthApp::new("Iv1.abc", Some("secret".to_string()))
+            .unwrap()
+            .with_web_url(server.url());
+        let refreshed = Arc::new(AtomicUsize::new(0));
+        let counter = refreshed.clone();
+        let credentials = OAuthUserCredentials::new(
+            app,
+            UserToken {
+                access_token: "ghu_old".into(),
+                token_type: None,
+                scope: None,
+                expires_at: Some(Utc::now() - ChronoDuration::minutes(1)),
+                refresh_token: Some("ghr_old".into()),
+                refresh_token_expires_at: None,
+            },
+        )
+        .on_refresh(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        assert_eq!(credentials.token().await.unwrap().as_str(), "ghu_new");
+        assert_eq!(credentials.token().await.unwrap().as_str(), "ghu_new");
+        assert_eq!(
+            credentials
+                .current()
+                .await
+                .refresh_token
+                .as_ref()
+                .map(SecretString::expose),
+            Some("ghr_new")
+        );
+        assert_eq!(refreshed.load(Ordering::SeqCst), 1);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_refresh_error_is_auth_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/login/oauth/access_token")
+            .with_status(200)
+            .with_body(r#"{"error": "bad_refresh_token", "error_description": "The refresh token passed is incorrect or expired."}"#)
+            .create_async()
+            .await;
+
+        let app = OAuthApp::new("Iv1.abc", None)
+            .unwrap()
+            .with_web_url(server.url());
+        let error = app.refresh_token("ghr_bad").await.unwrap_err();
+        assert!(
+            matches!(error, GitHubError::AuthError(ref m) if m.starts_with("bad_refresh_token"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_polls_until_authorized() {
+        let mut server = mockito::Server::new_async().await;
+        let device = server
+            .mock("POST", "/login/device/code")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("client_id".into(), "Iv1.abc".into()),
+                Matcher::UrlEncoded("scope".into(), "repo read:org".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "device_code": "3584d83530557fdd1f46af8289938c8ef79f9dc5",
+                    "user_code": "WDJB-MJHT",
+                    "verification_uri": "https://github.com/login/device",
+                    "expires_in": 900,
+                    "interval": 0
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let token_body = Matcher::AllOf(vec![
+            Matcher::UrlEncoded("grant_type".into(), DEVICE_CODE_GRANT_TYPE.into()),
+            Matcher::UrlEncoded(
+                "device_code".into(),
+                "3584d83530557fdd1f46af8289938c8ef79f9dc5".into(),
+            ),
+        ]);
+        let pending = server
+            .mock("POST", "/login/oauth/access_token")
+            .match_body(token_body.clone())
+            .with_status(200)
+            .with_body(r#"{"error": "authorization_pending"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let slow_down = server
+            .mock("POST", "/login/oauth/access_token")
+            .match_body(token_body.clone())
+            .with_status(200)
+            .with_body(r#"{"error": "slow_down", "interval": 0}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let granted = server
+            .mock("POST", "/login/oauth/access_token")
+            .match_body(token_body)
+            .with_status(200)
+            .with_body(
+                r#"{"access_token": "ghu_device", "token_type": "bearer", "scope": "repo,read:org"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let app = OAuthApp::new("Iv1.abc", None)
+            .unwrap()
+            .with_web_url(server.url());
+        let code = app
+            .request_device_code(&["repo", "read:org"])
+            .await
+            .unwrap();
+        assert_eq!(code.user_code, "WDJB-MJHT");
+
+        let token = app.poll_device_token(&code).await.unwrap();
+        assert_eq!(token.access_token.expose(), "ghu_device");
+        assert_eq!(token.expires_at, None);
+        device.assert_async().await;
+        pending.assert_async().await;
+        slow_down.assert_async().await;
+        granted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_access_denied() {
+        let mut server = mockito::Server::new_async().await;
+        let _denied = server
+            .mock("POST", "/login/oauth/access_token")
+            .with_status(200)
+            .with_body(r#"{"error": "access_denied", "error_description": "The authorization request was denied."}"#)
+            .create_async()
+            .await;
+
+        let app = OAuthApp::new("Iv1.abc", None)
+            .unwrap()
+            .with_web_url(server.url());
+        let code = DeviceCode {
+            device_code: "dc".to_string(),
+            user_code: "WDJB-MJHT".to_string(),
+            verification_uri: "https://github.com/login/device".to_string(),
+            expires_in: 900,
+            interval: 0,
+        };
+        let error = app.poll_device_token(&code).await.unwrap_err();
+        assert!(matches!(error, GitHubError::AuthError(ref m) if m.starts_with("access_denied")));
+    }
+
+    #[tokio::test]
+    async fn test_web_flow() {
+        let mut server = mockito::Server::new_async().await;
+        let exchange = server
+            .mock("POST", "/login/oauth/access_token")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("client_id".into(), "Iv1.abc".into()),
+                Matcher::UrlEncoded("client_secret".into(), "secret".into()),
+                Matcher::UrlEncoded("code".into(), "abc123".into()),
+                Matcher::UrlEncoded(
+                    "redirect_uri".into(),
+                    "http://localhost:8080/callback".into(),
+                ),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "access_token": "ghu_web",
+                    "expires_in": 28800,
+                    "refresh_token": "ghr_web",
+                    "refresh_token_expires_in": 15811200,
+                    "token_type": "bearer",
+                    "scope": ""
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let app = OAuthApp::new("Iv1.abc", Some("secret".to_string()))
+            .unwrap()
+            .with_web_url(server.url());
+        let url = app
+            .authorize_url(Some("http://localhost:8080/callback"), &["repo"], "xyz")
+            .unwrap();
+        assert_eq!(
+            url,
+            format!(
+                "{}/login/oauth/authorize?client_id=Iv1.abc&redirect_uri=http%3A%2F%2Flocalhost%3A8080%2Fcallback&scope=repo&state=xyz",
+                server.url()
+            )
+        );
+
+        let token = app
+            .exchange_code("abc123", Some("http://localhost:8080/callback"))
+            .await
+            .unwrap();
+        assert_eq!(token.access_token.expose(), "ghu_web");
+        assert_eq!(
+            token.refresh_token.as_ref().map(SecretString::expose),
+            Some("ghr_web")
+        );
+        assert!(!token.needs_refresh());
+        exchange.assert_async().await;
+    }
+}
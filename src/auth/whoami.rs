@@ -0,0 +1,264 @@
+use crate::client::GitHubClient;
+use crate::errors::GitHubError;
+use crate::models::User;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+
+/// classic PAT / OAuth のスコープと、それに含まれる下位スコープ
+const IMPLIED_SCOPES: &[(&str, &[&str])] = &[
+    (
+        "repo",
+        &[
+            "repo:status",
+            "repo_deployment",
+            "public_repo",
+            "repo:invite",
+            "security_events",
+        ],
+    ),
+    ("admin:org", &["write:org", "read:org"]),
+    ("write:org", &["read:org"]),
+    ("admin:public_key", &["write:public_key", "read:public_key"]),
+    ("write:public_key", &["read:public_key"]),
+    ("admin:repo_hook", &["write:repo_hook", "read:repo_hook"]),
+    ("write:repo_hook", &["read:repo_hook"]),
+    ("admin:gpg_key", &["write:gpg_key", "read:gpg_key"]),
+    ("write:gpg_key", &["read:gpg_key"]),
+    ("user", &["read:user", "user:email", "user:follow"]),
+    ("write:packages", &["read:packages"]),
+    ("project", &["read:project"]),
+];
+
+/// 認証に使っている主体
+#[derive(Debug, Clone)]
+pub enum Identity {
+    /// PAT や OAuth トークンで認証したユーザー
+    User(User),
+    /// GitHub App のインストールトークン（`/user` にアクセスできない）
+    Installation {
+        /// インストールがアクセスできるリポジトリ数
+        repository_count: u64,
+    },
+}
+
+/// `whoami()` の結果
+#[derive(Debug, Clone)]
+pub struct WhoAmI {
+    pub identity: Identity,
+    /// `x-oauth-scopes` で返されたスコープ（fine-grained PAT や App のトークンでは `None`）
+    pub scopes: Option<Vec<String>>,
+    /// `github-authentication-token-expiration` で返された失効時刻
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl WhoAmI {
+    fn from_headers(identity: Identity, headers: &HeaderMap) -> Self {
+        Self {
+            identity,
+            scopes: header_str(headers, "x-oauth-scopes").map(parse_scopes),
+            expires_at: header_str(headers, "github-authentication-token-expiration")
+                .and_then(parse_expiration),
+        }
+    }
+
+    /// スコープを持っているか（上位スコープによる包含も考慮する）
+    ///
+    /// スコープの情報が無いトークンでは常に `true` を返す。
+    pub fn has_scope(&self, scope: &str) -> bool {
+        let Some(scopes) = &self.scopes else {
+            return true;
+        };
+        scopes.iter().any(|granted| {
+            granted == scope
+                || IMPLIED_SCOPES
+                    .iter()
+                    .any(|(parent, implied)| granted == parent && implied.contains(&scope))
+        })
+    }
+
+    /// 必要なスコープがすべて付与されているか確認し、不足していれば `AuthError` を返す
+    pub fn require_scopes(&self, required: &[&str]) -> Result<(), GitHubError> {
+        let missing: Vec<&str> = required
+            .iter()
+            .copied()
+            .filter(|scope| !self.has_scope(scope))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(GitHubError::AuthError(format!(
+                "Token is missing required scopes: {}",
+                missing.join(", ")
+            )))
+        }
+    }
+
+    /// 失効時刻を過ぎているか
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+#[derive(Deserialize)]
+struct InstallationRepositories {
+    total_count: u64,
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn parse_scopes(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|scope| !scope.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// 例: `2023-03-24 11:07:06 UTC`、`2023-03-24 11:07:06 +0900`
+fn parse_expiration(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Some(naive) = value.strip_suffix(" UTC") {
+        return NaiveDateTime::parse_from_str(naive, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .map(|dt| dt.and_utc());
+    }
+    DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S %z")
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+impl GitHubClient {
+    /// 認証している主体とトークンの情報を取得する
+    ///
+    /// 起動時に呼び出し、`require_scopes` で権限不足のトークンを早期に検出する用途を想定している。
+    pub async fn whoami(&self) -> Result<WhoAmI, GitHubError> {
+        match self.get("/user").await {
+            Ok(response) => {
+                let headers = response.headers().clone();
+                let user: User = response.json().await?;
+                Ok(WhoAmI::from_headers(Identity::User(user), &headers))
+            }
+            // インストールトークンは `/user` にアクセスできない
+            Err(GitHubError::Forbidden { .. }) => {
+                let response = self.get("/installation/repositories?per_page=1").await?;
+                let headers = response.headers().clone();
+                let repositories: InstallationRepositories = response.json().await?;
+                Ok(WhoAmI::from_headers(
+                    Identity::Installation {
+                        repository_count: repositories.total_count,
+                    },
+                    &headers,
+                ))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_expiration() {
+        let expected = Utc.with_ymd_and_hms(2023, 3, 24, 11, 7, 6).unwrap();
+        assert_eq!(parse_expiration("2023-03-24 11:07:06 UTC"), Some(expected));
+        assert_eq!(
+            parse_expiration("2023-03-24 20:07:06 +0900"),
+            Some(expected)
+        );
+        assert_eq!(parse_expiration("never"), None);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let whoami = WhoAmI {
+            identity: Identity::Installation {
+                repository_count: 0,
+            },
+            scopes: Some(parse_scopes("repo, admin:org")),
+            expires_at: None,
+        };
+        assert!(whoami.has_scope("repo"));
+        assert!(whoami.has_scope("public_repo"));
+        assert!(whoami.has_scope("read:org"));
+        assert!(!whoami.has_scope("workflow"));
+        assert!(whoami.require_scopes(&["repo", "read:org"]).is_ok());
+        let error = whoami
+            .require_scopes(&["repo", "workflow", "gist"])
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::AuthError(ref m) if m.ends_with("workflow, gist")));
+    }
+
+    #[tokio::test]
+    async fn test_whoami_user() {
+        let mut server = mockito::Server::new_async().await;
+        let _user = server
+            .mock("GET", "/user")
+            .match_header("authorization", "Bearer github_pat_test")
+            .with_status(200)
+            .with_header("x-oauth-scopes", "repo, workflow")
+            .with_header(
+                "github-authentication-token-expiration",
+                "2030-01-01 00:00:00 UTC",
+            )
+            .with_body(r#"{"login": "octocat", "id": 1, "node_id": "MDQ6VXNlcjE="}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::builder()
+            .token("github_pat_test")
+            .auth_scheme(crate::auth::AuthScheme::Bearer)
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        let whoami = client.whoami().await.unwrap();
+        assert!(matches!(whoami.identity, Identity::User(ref u) if u.login == "octocat"));
+        assert_eq!(
+            whoami.scopes,
+            Some(vec!["repo".to_string(), "workflow".to_string()])
+        );
+        assert_eq!(
+            whoami.expires_at,
+            Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())
+        );
+        assert!(!whoami.is_expired());
+    }
+
+    #[tokio::test]
+    async fn test_whoami_installation() {
+        let mut server = mockito::Server::new_async().await;
+        let _user = server
+            .mock("GET", "/user")
+            .with_status(403)
+            .with_body(r#"{"message": "Resource not accessible by integration"}"#)
+            .create_async()
+            .await;
+        let _repos = server
+            .mock("GET", "/installation/repositories?per_page=1")
+            .with_status(200)
+            .with_body(r#"{"total_count": 3, "repositories": []}"#)
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("ghs_test".to_string());
+        client.base_url = server.url();
+
+        let whoami = client.whoami().await.unwrap();
+        assert!(matches!(
+            whoami.identity,
+            Identity::Installation {
+                repository_count: 3
+            }
+        ));
+        assert_eq!(whoami.scopes, None);
+        assert!(whoami.has_scope("repo"));
+    }
+}
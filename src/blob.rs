@@ -0,0 +1,306 @@
+use crate::client::GitHubClient;
+use crate::errors::GitHubError;
+use crate::models::Blob;
+use base64::engine::general_purpose::STANDARD;
+use base64::write::EncoderStringWriter;
+use base64::Engine;
+use bytes::Bytes;
+use sha1::{Digest, Sha1};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tracing::debug;
+
+/// GitHub が受け付けるBLOBの最大サイズ（100 MiB）
+pub const MAX_BLOB_SIZE: u64 = 100 * 1024 * 1024;
+
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 内容から git の BLOB SHA-1 を計算する
+///
+/// `blob {len}\0` ヘッダーを付けてハッシュするため、GitHub 上の SHA と一致する。
+pub fn git_blob_sha(content: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content);
+    format!("{:x}", hasher.finalize())
+}
+
+fn check_size(size: u64) -> Result<(), GitHubError> {
+    if size > MAX_BLOB_SIZE {
+        return Err(GitHubError::BlobTooLarge {
+            size,
+            limit: MAX_BLOB_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// API が返す `content` を `encoding` に従ってデコードする
+///
+/// base64 は60文字ごとに改行されているため、空白を取り除いてからデコードする。
+pub(crate) fn decode_content(
+    content: &str,
+    encoding: Option<&str>,
+) -> Result<Vec<u8>, GitHubError> {
+    match encoding {
+        Some("utf-8") => Ok(content.as_bytes().to_vec()),
+        Some("base64") | None => {
+            let encoded: String = content.split_whitespace().collect();
+            STANDARD
+                .decode(encoded)
+                .map_err(|e| GitHubError::ParseError(format!("Invalid base64 content: {}", e)))
+        }
+        Some(other) => Err(GitHubError::ParseError(format!(
+            "Unsupported content encoding: {}",
+            other
+        ))),
+    }
+}
+
+impl Blob {
+    /// `content` をデコードしたバイト列（取得したBLOBのみ内容を持つ）
+    pub fn decoded_content(&self) -> Result<Vec<u8>, GitHubError> {
+        let content = self
+            .content
+            .as_deref()
+            .ok_or_else(|| GitHubError::ParseError(format!("Blob {} has no content", self.sha)))?;
+        decode_content(content, self.encoding.as_deref())
+    }
+}
+
+impl GitHubClient {
+    /// BLOBを取得する（`content` は base64 のまま）
+    pub async fn get_blob(&self, owner: &str, repo: &str, sha: &str) -> Result<Blob, GitHubError> {
+        let path = format!("/repos/{}/{}/git/blobs/{}", owner, repo, sha);
+        Ok(self.get(&path).await?.json().await?)
+    }
+
+    /// BLOBの内容を取得し、base64 をデコードして返す
+    pub async fn get_blob_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<Bytes, GitHubError> {
+        let blob = self.get_blob(owner, repo, sha).await?;
+        Ok(blob.decoded_content()?.into())
+    }
+
+    /// バイト列を base64 エンコードしてBLOBを作成する
+    ///
+    /// 画像やアーカイブなど UTF-8 ではない内容もそのまま扱える。
+    pub async fn create_blob_from_bytes(
+        &self,
+        owner: &str,
+        repo: &str,
+        content: &[u8],
+    ) -> Result<Blob, GitHubError> {
+        check_size(content.len() as u64)?;
+        self.post_base64_blob(owner, repo, STANDARD.encode(content))
+            .await
+    }
+
+    /// `existing` に同じ SHA が含まれていればアップロードせずにBLOBを返す
+    ///
+    /// ツリーに既に存在する内容を再送しないために使う。
+    pub async fn create_blob_if_missing(
+        &self,
+        owner: &str,
+        repo: &str,
+        content: &[u8],
+        existing: &HashSet<String>,
+    ) -> Result<Blob, GitHubError> {
+        let sha = git_blob_sha(content);
+        if existing.contains(&sha) {
+            debug!(target: "github_client", %sha, "Blob already exists, skipping upload");
+            return Ok(Blob {
+                url: format!(
+                    "{}/repos/{}/{}/git/blobs/{}",
+                    self.base_url, owner, repo, sha
+                ),
+                sha,
+                node_id: None,
+                content: None,
+                encoding: None,
+                size: Some(content.len() as u64),
+            });
+        }
+        self.create_blob_from_bytes(owner, repo, content).await
+    }
+
+    /// `AsyncRead` から読み込みながら base64 エンコードしてBLOBを作成する
+    ///
+    /// 送信する base64 文字列（元データの約4/3倍）はメモリ上に組み立てる。
+    /// 読み込みながらサイズを確認し、上限を超えた時点でエラーを返す。
+    pub async fn create_blob_from_reader<R>(
+        &self,
+        owner: &str,
+        repo: &str,
+        mut reader: R,
+    ) -> Result<Blob, GitHubError>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut encoder = EncoderStringWriter::new(&STANDARD);
+        let mut buf = vec![0u8; READ_CHUNK_SIZE];
+        let mut total: u64 = 0;
+
+        loop {
+            let n = reader.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            total += n as u64;
+            check_size(total)?;
+            encoder.write_all(&buf[..n])?;
+        }
+
+        debug!(target: "github_client", size = total, "Read blob content from stream");
+        self.post_base64_blob(owner, repo, encoder.into_inner())
+            .await
+    }
+
+    /// ファイルを読み込んでBLOBを作成する
+    ///
+    /// アップロード前にファイルサイズを確認し、上限を超える場合はエラーを返す。
+    pub async fn create_blob_from_path(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<Blob, GitHubError> {
+        let file = tokio::fs::File::open(path.as_ref()).await?;
+        check_size(file.metadata().await?.len())?;
+        self.create_blob_from_reader(owner, repo, file).await
+    }
+
+    async fn post_base64_blob(
+        &self,
+        owner: &str,
+        repo: &str,
+        encoded: String,
+    ) -> Result<Blob, GitHubError> {
+        let path = format!("/repos/{}/{}/git/blobs", owner, repo);
+        let body = serde_json::json!({
+            "content": encoded,
+            "encoding": "base64"
+        });
+
+        Ok(self.post_idempotent(&path, &body).await?.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    #[test]
+    fn test_git_blob_sha() {
+        assert_eq!(
+            git_blob_sha(b""),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            git_blob_sha(b"hello\n"),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_blob_content_decodes_base64() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/repos/owner/repo/git/blobs/3a0f86fb")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "sha": "3a0f86fb",
+                    "url": "https://api.github.com/x",
+                    "content": "aGVsbG8g\nd29ybGQ=\n",
+                    "encoding": "base64",
+                    "size": 11
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let content = client
+            .get_blob_content("owner", "repo", "3a0f86fb")
+            .await
+            .unwrap();
+        assert_eq!(&content[..], b"hello world");
+        assert_eq!(decode_content("plain", Some("utf-8")).unwrap(), b"plain");
+        assert!(decode_content("x", Some("none")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_blob_from_bytes_sends_base64() {
+        let content = [0x89u8, b'P', b'N', b'G', 0x00, 0xff];
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/repos/owner/repo/git/blobs")
+            .match_body(Matcher::Json(json!({
+                "content": "iVBORwD/",
+                "encoding": "base64"
+            })))
+            .with_status(201)
+            .with_body(r#"{"sha": "b10b", "url": "https://api.github.com/x"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let blob = client
+            .create_blob_from_bytes("owner", "repo", &content)
+            .await
+            .unwrap();
+        assert_eq!(blob.sha, "b10b");
+
+        let blob = client
+            .create_blob_from_reader("owner", "repo", &content[..])
+            .await
+            .unwrap();
+        assert_eq!(blob.sha, "b10b");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_blob_if_missing_skips_known_sha() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/repos/owner/repo/git/blobs")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let existing = HashSet::from([git_blob_sha(b"hello\n")]);
+        let blob = client
+            .create_blob_if_missing("owner", "repo", b"hello\n", &existing)
+            .await
+            .unwrap();
+        assert_eq!(blob.sha, "ce013625030ba8dba906f756967f9e9ca394464a");
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_check_size() {
+        assert!(check_size(MAX_BLOB_SIZE).is_ok());
+        assert!(matches!(
+            check_size(MAX_BLOB_SIZE + 1),
+            Err(GitHubError::BlobTooLarge { .. })
+        ));
+    }
+}
@@ -0,0 +1,484 @@
+use crate::client::GitHubClient;
+use crate::errors::GitHubError;
+use chrono::{DateTime, Utc};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+    SET_COOKIE,
+};
+use reqwest::{Method, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// `MemoryCache` の既定の最大エントリ数
+pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+/// 既定の合計サイズの上限（64 MiB）
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// キャッシュされたレスポンス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    #[serde(skip)]
+    pub body: Vec<u8>,
+    pub stored_at: DateTime<Utc>,
+}
+
+impl CachedResponse {
+    fn header(&self, name: &HeaderName) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name.as_str()))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        self.header(&ETAG)
+    }
+
+    pub fn last_modified(&self) -> Option<&str> {
+        self.header(&LAST_MODIFIED)
+    }
+
+    fn is_expired(&self, ttl: Option<Duration>) -> bool {
+        ttl.is_some_and(|ttl| {
+            (Utc::now() - self.stored_at)
+                .to_std()
+                .is_ok_and(|age| age > ttl)
+        })
+    }
+
+    fn size(&self) -> u64 {
+        self.body.len() as u64
+    }
+
+    fn from_parts(status: StatusCode, headers: &HeaderMap, body: Vec<u8>) -> Self {
+        Self {
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter(|(name, _)| *name != SET_COOKIE)
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body,
+            stored_at: Utc::now(),
+        }
+    }
+
+    fn into_response(self) -> Result<Response, GitHubError> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        builder
+            .body(self.body)
+            .map(Response::from)
+            .map_err(|e| GitHubError::ParseError(format!("Invalid cached response: {}", e)))
+    }
+}
+
+/// 条件付きリクエストに使うレスポンスキャッシュ
+///
+/// キーは URL と認証情報のハッシュから作られるため、トークンそのものは保存されない。
+pub trait ResponseCache: Send + Sync {
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+    fn put(&self, key: &str, response: CachedResponse);
+    fn remove(&self, key: &str);
+}
+
+/// URL と認証情報からキャッシュキーを作る
+pub fn cache_key(url: &str, token: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(token.as_bytes());
+    format!("{} {:x}", url, hasher.finalize())
+}
+
+/// プロセス内のメモリに保存するキャッシュ
+///
+/// 上限を超えたら最も長く使われていないエントリから破棄する。
+pub struct MemoryCache {
+    max_entries: usize,
+    max_bytes: u64,
+    ttl: Option<Duration>,
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    entries: HashMap<String, (CachedResponse, u64)>,
+    bytes: u64,
+    clock: u64,
+}
+
+impl MemoryState {
+    fn remove(&mut self, key: &str) {
+        if let Some((entry, _)) = self.entries.remove(key) {
+            self.bytes -= entry.size();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, (_, used))| *used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.remove(&key);
+        }
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_bytes: DEFAULT_MAX_BYTES,
+            ttl: None,
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// レスポンスボディの合計サイズの上限
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// 保存してからこの時間を過ぎたエントリは使わない
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut state = self.state();
+        state.clock += 1;
+        let clock = state.clock;
+        let (entry, used) = state.entries.get_mut(key)?;
+        if entry.is_expired(self.ttl) {
+            state.remove(key);
+            return None;
+        }
+        *used = clock;
+        Some(entry.clone())
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        if response.size() > self.max_bytes {
+            return;
+        }
+        let mut state = self.state();
+        state.remove(key);
+        while !state.entries.is_empty()
+            && (state.entries.len() >= self.max_entries
+                || state.bytes + response.size() > self.max_bytes)
+        {
+            state.evict_oldest();
+        }
+        state.clock += 1;
+        state.bytes += response.size();
+        let clock = state.clock;
+        state.entries.insert(key.to_string(), (response, clock));
+    }
+
+    fn remove(&self, key: &str) {
+        self.state().remove(key);
+    }
+}
+
+/// ディレクトリにファイルとして保存するキャッシュ
+///
+/// 1エントリ1ファイルで、1行目にメタデータの JSON、以降にボディを書き込む。
+/// 合計サイズが上限を超えたら更新日時の古いファイルから削除する。
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    ttl: Option<Duration>,
+}
+
+impl DiskCache {
+    /// ディレクトリが無ければ作成する
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, GitHubError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            max_bytes: DEFAULT_MAX_BYTES,
+            ttl: None,
+        })
+    }
+
+    /// ファイルの合計サイズの上限
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// 保存してからこの時間を過ぎたエントリは使わない
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let mut hasher = Sha1::new();
+        hasher.update(key.as_bytes());
+        self.dir.join(format!("{:x}", hasher.finalize()))
+    }
+
+    fn read(&self, key: &str) -> std::io::Result<CachedResponse> {
+        let mut reader = BufReader::new(std::fs::File::open(self.path(key))?);
+        let mut meta = String::new();
+        reader.read_line(&mut meta)?;
+        let mut response: CachedResponse = serde_json::from_str(&meta)?;
+        reader.read_to_end(&mut response.body)?;
+        Ok(response)
+    }
+
+    fn write(&self, key: &str, response: &CachedResponse) -> std::io::Result<()> {
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        let mut file = std::fs::File::create(&tmp)?;
+        serde_json::to_writer(&mut file, response)?;
+        file.write_all(b"\n")?;
+        file.write_all(&response.body)?;
+        file.sync_all()?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// 合計サイズが上限に収まるまで古いファイルを削除する
+    fn prune(&self) -> std::io::Result<()> {
+        let mut files = Vec::new();
+        let mut total = 0;
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let meta = entry.metadata()?;
+            if meta.is_file() {
+                total += meta.len();
+                files.push((meta.modified()?, meta.len(), entry.path()));
+            }
+        }
+        files.sort();
+        for (_, len, path) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            std::fs::remove_file(path)?;
+            total -= len;
+        }
+        Ok(())
+    }
+}
+
+impl ResponseCache for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let response = self.read(key).ok()?;
+        if response.is_expired(self.ttl) {
+            self.remove(key);
+            return None;
+        }
+        Some(response)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        if response.size() > self.max_bytes {
+            return;
+        }
+        if let Err(e) = self.write(key, &response).and_then(|_| self.prune()) {
+            warn!(target: "github_client", error = %e, "Failed to write response cache");
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = std::fs::remove_file(self.path(key));
+    }
+}
+
+impl GitHubClient {
+    /// キャッシュを使った GET
+    ///
+    /// キャッシュがあれば `If-None-Match` / `If-Modified-Since` を付けて送り、
+    /// 304 が返ったらキャッシュしたボディを 200 のレスポンスとして返す。
+    pub(crate) async fn cached_get(
+        &self,
+        cache: &dyn ResponseCache,
+        path: &str,
+    ) -> Result<Response, GitHubError> {
+        let url = format!("{}{}", self.base_url, path);
+        let key = cache_key(&url, self.credentials.token().await?.as_str());
+        let cached = cache.get(&key);
+
+        let mut request = self.request(Method::GET, path);
+        if let Some(entry) = &cached {
+            if let Some(etag) = entry.etag().and_then(|v| HeaderValue::from_str(v).ok()) {
+                request = request.header(IF_NONE_MATCH, etag);
+            } else if let Some(since) = entry
+                .last_modified()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                request = request.header(IF_MODIFIED_SINCE, since);
+            }
+        }
+
+        let response = self.send(Method::GET, path, request, true).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                debug!(target: "github_client", endpoint = %path, "Serving response from cache");
+                return entry.into_response();
+            }
+            return Ok(response);
+        }
+
+        let headers = response.headers();
+        if !response.status().is_success()
+            || !(headers.contains_key(ETAG) || headers.contains_key(LAST_MODIFIED))
+        {
+            return Ok(response);
+        }
+        let status = response.status();
+        let headers = headers.clone();
+        let body = response.bytes().await?.to_vec();
+        let entry = CachedResponse::from_parts(status, &headers, body);
+        cache.put(&key, entry.clone());
+        entry.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GitRef;
+
+    fn entry(body: &str) -> CachedResponse {
+        CachedResponse {
+            status: 200,
+            headers: vec![("etag".to_string(), "\"abc\"".to_string())],
+            body: body.as_bytes().to_vec(),
+            stored_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_memory_cache_limits() {
+        let cache = MemoryCache::new().with_max_entries(2);
+        cache.put("a", entry("1"));
+        cache.put("b", entry("2"));
+        assert!(cache.get("a").is_some());
+        cache.put("c", entry("3"));
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.len(), 2);
+
+        let cache = MemoryCache::new().with_max_bytes(4);
+        cache.put("a", entry("12"));
+        cache.put("b", entry("345"));
+        assert!(cache.get("a").is_none());
+        cache.put("huge", entry("123456"));
+        assert!(cache.get("huge").is_none());
+
+        let cache = MemoryCache::new().with_ttl(Duration::from_secs(60));
+        let mut stale = entry("old");
+        stale.stored_at = Utc::now() - chrono::Duration::minutes(2);
+        cache.put("a", stale);
+        assert!(cache.get("a").is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("github-rs-cache-{}", std::process::id()));
+        let cache = DiskCache::new(&dir).unwrap();
+        cache.put("key", entry("{\"line\":\n\"body\"}"));
+        let cached = cache.get("key").unwrap();
+        assert_eq!(cached.etag(), Some("\"abc\""));
+        assert_eq!(cached.body, b"{\"line\":\n\"body\"}");
+        cache.remove("key");
+        assert!(cache.get("key").is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_token() {
+        let url = "https://api.github.com/user";
+        assert_eq!(cache_key(url, "a"), cache_key(url, "a"));
+        assert_ne!(cache_key(url, "a"), cache_key(url, "b"));
+        assert!(!cache_key(url, "ghp_secret").contains("ghp_secret"));
+    }
+
+    #[tokio::test]
+    async fn test_serves_cached_body_on_not_modified() {
+        let body = r#"{"ref": "refs/heads/main", "url": "https://api.github.com/x", "object": {"sha": "aa218f56b14c9653891f9e74264a383fa43fefbd", "type": "commit", "url": "https://api.github.com/y"}}"#;
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/main")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("etag", "\"v1\"")
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await;
+        let revalidated = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/main")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .with_header("etag", "\"v1\"")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.url())
+            .cache(MemoryCache::new())
+            .build()
+            .unwrap();
+
+        for _ in 0..3 {
+            let git_ref: GitRef = client
+                .get_branch_ref("owner", "repo", "main")
+                .await
+                .unwrap();
+            assert_eq!(
+                git_ref.object.sha,
+                "aa218f56b14c9653891f9e74264a383fa43fefbd"
+            );
+        }
+        first.assert_async().await;
+        revalidated.assert_async().await;
+    }
+}
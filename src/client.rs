@@ -1,118 +1,546 @@
-use crate::auth::{build_auth_headers, AuthToken};
-use reqwest::{Client, Response};
-use serde_json::Value;
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum GitHubError {
-    #[error("HTTP request failed: {0}")]
-    RequestError(#[from] reqwest::Error),
-    #[error("Failed to parse response: {0}")]
-    ParseError(String),
-    #[error("Request failed with status {status}: {message}")]
-    ApiError {
-        status: reqwest::StatusCode,
-        message: String,
-    },
+use crate::auth::credentials::CredentialProvider;
+use crate::auth::{build_auth_headers, AuthScheme, AuthToken};
+use crate::cache::ResponseCache;
+use crate::commit::CommitBuilder;
+pub use crate::errors::GitHubError;
+use crate::models::{
+    Blob, Commit, GitRef, NewCommit, NewTreeEntry, PullRequest, Repository, Tree, User,
+};
+use crate::rate_limit::{RateLimit, RateLimitPolicy};
+use crate::refs::check_branch_name;
+use crate::retry::{is_idempotent, RetryPolicy};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, USER_AGENT};
+use reqwest::{Certificate, Client, Method, Proxy, RequestBuilder, Response, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// GitHub.com の REST API のベース URL
+pub const DEFAULT_BASE_URL: &str = "https://api.github.com";
+/// GitHub.com のアップロード用 URL
+pub const DEFAULT_UPLOAD_URL: &str = "https://uploads.github.com";
+/// `X-GitHub-Api-Version` ヘッダーの既定値
+pub const DEFAULT_API_VERSION: &str = "2022-11-28";
+pub(crate) const DEFAULT_USER_AGENT: &str = "github-rs-client";
+
+/// パスに埋め込むときにエンコードしない文字（`/` は階層の区切りとしてそのまま送る）
+const PATH_SEGMENTS: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'/');
+
+/// クエリの値としてエンコードしない文字
+const QUERY_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// ブランチ名やファイルパスを URL のパスに埋め込めるようにエンコードする
+///
+/// `feature/fix#123` は `feature/fix%23123` になる。
+pub(crate) fn encode_path(path: &str) -> String {
+    utf8_percent_encode(path, PATH_SEGMENTS).to_string()
 }
 
-impl GitHubError {
-    pub fn status(&self) -> Option<reqwest::StatusCode> {
-        match self {
-            GitHubError::RequestError(e) => e.status(),
-            GitHubError::ApiError { status, .. } => Some(*status),
-            _ => None,
-        }
-    }
+/// クエリパラメーターの値をエンコードする
+pub(crate) fn encode_query(value: &str) -> String {
+    utf8_percent_encode(value, QUERY_VALUE).to_string()
 }
 
 pub struct GitHubClient {
     http: Client,
-    token: AuthToken,
-    base_url: String,
+    pub(crate) credentials: Arc<dyn CredentialProvider>,
+    auth_scheme: Option<AuthScheme>,
+    cache: Option<Arc<dyn ResponseCache>>,
+    pub(crate) base_url: String,
+    pub(crate) graphql_url: Option<String>,
+    upload_url: String,
+    pub(crate) rate_limit: Mutex<Option<RateLimit>>,
+    pub(crate) graphql_rate_limit: Mutex<Option<RateLimit>>,
+    rate_limit_policy: RateLimitPolicy,
+    retry_policy: RetryPolicy,
+}
+
+/// `GitHubClient` のビルダー
+///
+/// GitHub Enterprise Server の URL、タイムアウト、プロキシ、ルート証明書、
+/// 追加のデフォルトヘッダーなどを設定できる。
+///
+/// ```no_run
+/// # fn run() -> Result<(), github::errors::GitHubError> {
+/// use std::time::Duration;
+///
+/// let client = github::client::GitHubClient::builder()
+///     .token("ghp_xxx")
+///     .enterprise("github.example.com")
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(30))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct GitHubClientBuilder {
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    auth_scheme: Option<AuthScheme>,
+    cache: Option<Arc<dyn ResponseCache>>,
+    base_url: Option<String>,
+    graphql_url: Option<String>,
+    upload_url: Option<String>,
+    user_agent: Option<String>,
+    api_version: Option<Option<String>>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    root_certificates: Vec<Certificate>,
+    default_headers: Vec<(String, String)>,
+    rate_limit_policy: RateLimitPolicy,
+    retry_policy: RetryPolicy,
+}
+
+impl GitHubClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 認証に使う固定のトークン
+    pub fn token(self, token: impl Into<String>) -> Self {
+        self.credentials(AuthToken::new(token))
+    }
+
+    /// リクエストごとにトークンを提供するプロバイダー
+    pub fn credentials(mut self, provider: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(provider));
+        self
+    }
+
+    /// プロバイダーが返すトークンのスキームを上書きする
+    ///
+    /// fine-grained PAT を `token()` で渡す場合などに `AuthScheme::Bearer` を指定する。
+    pub fn auth_scheme(mut self, scheme: AuthScheme) -> Self {
+        self.auth_scheme = Some(scheme);
+        self
+    }
+
+    /// REST API のベース URL（例: `https://github.example.com/api/v3`）
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = Some(url.into());
+        self
+    }
+
+    /// GraphQL エンドポイントの URL（省略時はベース URL から決める）
+    pub fn graphql_url(mut self, url: impl Into<String>) -> Self {
+        self.graphql_url = Some(url.into());
+        self
+    }
+
+    /// アップロード用 URL（例: `https://github.example.com/api/uploads`）
+    pub fn upload_url(mut self, url: impl Into<String>) -> Self {
+        self.upload_url = Some(url.into());
+        self
+    }
+
+    /// GitHub Enterprise Server のホスト名からベース URL とアップロード URL を設定する
+    pub fn enterprise(self, host: &str) -> Self {
+        let host = host.trim_end_matches('/');
+        let origin = if host.starts_with("http://") || host.starts_with("https://") {
+            host.to_string()
+        } else {
+            format!("https://{}", host)
+        };
+        self.base_url(format!("{}/api/v3", origin))
+            .upload_url(format!("{}/api/uploads", origin))
+    }
+
+    /// `User-Agent` ヘッダー
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// `X-GitHub-Api-Version` ヘッダー（`None` で送信しない）
+    pub fn api_version(mut self, version: Option<&str>) -> Self {
+        self.api_version = Some(version.map(String::from));
+        self
+    }
+
+    /// 接続確立までのタイムアウト
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// レスポンスの読み込みまでを含むリクエスト全体のタイムアウト
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// すべてのリクエストに使うプロキシ
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// 信頼するルート証明書を追加する（社内 CA で署名された GHES など）
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// すべてのリクエストに付与するヘッダーを追加する
+    pub fn default_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// レート制限に達したときの待機ポリシー
+    pub fn rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.rate_limit_policy = policy;
+        self
+    }
+
+    /// 一時的な失敗に対する再試行ポリシー
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// GET レスポンスのキャッシュ（`ETag` / `Last-Modified` による条件付きリクエスト）
+    ///
+    /// 304 レスポンスはレート制限を消費しないため、同じリソースを頻繁に取得する場合に有効。
+    pub fn cache(mut self, cache: impl ResponseCache + 'static) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    pub fn build(self) -> Result<GitHubClient, GitHubError> {
+        let http = self.http_client()?;
+        let credentials = self.credentials.ok_or_else(|| {
+            GitHubError::ConfigError("token or credentials are required".to_string())
+        })?;
+
+        Ok(GitHubClient {
+            http,
+            credentials,
+            auth_scheme: self.auth_scheme,
+            cache: self.cache,
+            base_url: normalize_url(self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL))?,
+            graphql_url: self.graphql_url.as_deref().map(normalize_url).transpose()?,
+            upload_url: normalize_url(self.upload_url.as_deref().unwrap_or(DEFAULT_UPLOAD_URL))?,
+            rate_limit: Mutex::new(None),
+            graphql_rate_limit: Mutex::new(None),
+            rate_limit_policy: self.rate_limit_policy,
+            retry_policy: self.retry_policy,
+        })
+    }
+
+    /// ヘッダー・タイムアウト・プロキシ・ルート証明書を反映した HTTP クライアントを作成する
+    pub(crate) fn http_client(&self) -> Result<Client, GitHubError> {
+        let mut headers = HeaderMap::new();
+        let user_agent = self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+        headers.insert(USER_AGENT, header_value(user_agent)?);
+        let api_version = self
+            .api_version
+            .clone()
+            .unwrap_or_else(|| Some(DEFAULT_API_VERSION.to_string()));
+        if let Some(version) = api_version {
+            headers.insert("x-github-api-version", header_value(&version)?);
+        }
+        for (name, value) in &self.default_headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                GitHubError::ConfigError(format!("invalid header name {}: {}", name, e))
+            })?;
+            headers.insert(name, header_value(value)?);
+        }
+
+        let mut http = Client::builder().default_headers(headers);
+        if let Some(timeout) = self.connect_timeout {
+            http = http.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            http = http.proxy(proxy.clone());
+        }
+        for certificate in &self.root_certificates {
+            http = http.add_root_certificate(certificate.clone());
+        }
+        Ok(http.build()?)
+    }
+
+    /// 明示的に設定されたベース URL
+    pub(crate) fn configured_base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, GitHubError> {
+    HeaderValue::from_str(value)
+        .map_err(|e| GitHubError::ConfigError(format!("invalid header value: {}", e)))
+}
+
+fn normalize_url(url: &str) -> Result<String, GitHubError> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(GitHubError::ConfigError(format!(
+            "URL must start with http:// or https://: {}",
+            url
+        )));
+    }
+    Ok(url.trim_end_matches('/').to_string())
 }
 
 impl GitHubClient {
+    /// トークンと既定の設定でクライアントを作成する
+    ///
+    /// # Panics
+    ///
+    /// HTTP クライアントを作成できない場合（TLS バックエンドの初期化に失敗した場合など）に
+    /// パニックする。エラーとして受け取るには `GitHubClient::builder().token(token).build()` を使う。
     pub fn new(token: String) -> Self {
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            reqwest::header::USER_AGENT,
-            reqwest::header::HeaderValue::from_static("github-rs-client"),
-        );
-
-        let client = Client::builder()
-            .default_headers(headers)
+        Self::builder()
+            .token(token)
             .build()
-            .expect("Failed to create HTTP client");
+            .expect("Failed to create HTTP client")
+    }
+
+    /// 設定をカスタマイズするためのビルダーを作成する
+    pub fn builder() -> GitHubClientBuilder {
+        GitHubClientBuilder::new()
+    }
+
+    /// REST API のベース URL
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// アップロード用 URL
+    pub fn upload_url(&self) -> &str {
+        &self.upload_url
+    }
+
+    /// レート制限に達したときの待機ポリシーを設定する
+    pub fn with_rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.rate_limit_policy = policy;
+        self
+    }
+
+    /// 一時的な失敗に対する再試行ポリシーを設定する
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
 
-        Self {
-            http: client,
-            token: AuthToken::new(token),
-            base_url: "https://api.github.com".to_string(),
+    pub async fn get(&self, path: &str) -> Result<Response, GitHubError> {
+        if let Some(cache) = &self.cache {
+            return self.cached_get(cache.as_ref(), path).await;
         }
+        self.send(
+            Method::GET,
+            path,
+            self.request(Method::GET, path),
+            is_idempotent(&Method::GET),
+        )
+        .await
     }
 
-    pub async fn get(&self, path: &str) -> reqwest::Result<Response> {
-        use tracing::{debug, info, warn};
+    pub async fn post<T: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<Response, GitHubError> {
+        self.send(
+            Method::POST,
+            path,
+            self.request(Method::POST, path).json(body),
+            false,
+        )
+        .await
+    }
 
-        let url = format!("{}{}", self.base_url, path);
-        info!(target: "github_client", method = "GET", %url, "Making API request");
+    /// 再送しても結果が変わらない POST を送信する
+    ///
+    /// BLOB・ツリー・コミットは内容からSHAが決まるため、一時的な失敗時に再試行できる。
+    pub(crate) async fn post_idempotent<T: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<Response, GitHubError> {
+        self.send(
+            Method::POST,
+            path,
+            self.request(Method::POST, path).json(body),
+            true,
+        )
+        .await
+    }
 
-        let headers = build_auth_headers(self.token.as_str());
-        debug!(target: "github_client", ?headers, "Request headers prepared");
+    pub async fn patch<T: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<Response, GitHubError> {
+        self.send(
+            Method::PATCH,
+            path,
+            self.request(Method::PATCH, path).json(body),
+            false,
+        )
+        .await
+    }
 
-        let response = self.http.get(url).headers(headers).send().await?;
+    pub async fn delete(&self, path: &str) -> Result<Response, GitHubError> {
+        self.send(
+            Method::DELETE,
+            path,
+            self.request(Method::DELETE, path),
+            is_idempotent(&Method::DELETE),
+        )
+        .await
+    }
+
+    pub(crate) fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.request_url(method, &format!("{}{}", self.base_url, path))
+    }
+
+    /// ベース URL 以外（GraphQL やアップロード用 URL）へのリクエスト
+    pub(crate) fn request_url(&self, method: Method, url: &str) -> RequestBuilder {
+        self.http.request(method, url)
+    }
+
+    /// リクエストを送信し、レート制限・再試行ポリシーに従って待機・再送する
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        request: RequestBuilder,
+        idempotent: bool,
+    ) -> Result<Response, GitHubError> {
+        use tracing::warn;
+
+        let mut waits = 0;
+        let mut attempt_no = 1;
+        let mut reauthenticated = false;
+        loop {
+            let Some(attempt) = request.try_clone() else {
+                return self.send_once(&method, path, request).await;
+            };
+            let error = match self.send_once(&method, path, attempt).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+            if matches!(error, GitHubError::Unauthorized { .. })
+                && !reauthenticated
+                && self.credentials.invalidate().await
+            {
+                warn!(target: "github_client", endpoint = %path, "Credentials rejected, retrying with refreshed token");
+                reauthenticated = true;
+                continue;
+            }
+            match self.rate_limit_policy.wait_for(&error) {
+                Some(wait) if waits < self.rate_limit_policy.max_retries => {
+                    waits += 1;
+                    warn!(
+                        target: "github_client",
+                        endpoint = %path,
+                        wait_secs = wait.as_secs(),
+                        %error,
+                        "Rate limited, waiting before retrying"
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+                _ if self
+                    .retry_policy
+                    .should_retry(&error, attempt_no, idempotent) =>
+                {
+                    let delay = self.retry_policy.backoff(attempt_no);
+                    warn!(
+                        target: "github_client",
+                        %method,
+                        endpoint = %path,
+                        attempt = attempt_no,
+                        delay_ms = delay.as_millis() as u64,
+                        %error,
+                        "Transient failure, retrying request"
+                    );
+                    attempt_no += 1;
+                    tokio::time::sleep(delay).await;
+                }
+                _ => return Err(error),
+            }
+        }
+    }
+
+    /// リクエストを1回送信し、失敗レスポンスを `GitHubError` に変換する
+    async fn send_once(
+        &self,
+        method: &Method,
+        path: &str,
+        request: RequestBuilder,
+    ) -> Result<Response, GitHubError> {
+        use tracing::{info, warn};
+
+        info!(target: "github_client", %method, endpoint = %path, "Making API request");
+
+        let token = self.credentials.token().await?;
+        let mut headers =
+            build_auth_headers(token.as_str(), self.auth_scheme.unwrap_or(token.scheme()))?;
+        let (http, request) = request.build_split();
+        let mut request = request?;
+        // 呼び出し側が指定した `Accept`（raw メディアタイプなど）は上書きしない
+        if request.headers().contains_key(ACCEPT) {
+            headers.remove(ACCEPT);
+        }
+        request.headers_mut().extend(headers);
+        let response = http.execute(request).await?;
         let status = response.status();
+        self.record_rate_limit(response.headers());
 
-        if !status.is_success() {
+        // 304 は条件付きリクエストへの応答で、呼び出し側がキャッシュを返す
+        if !status.is_success() && status != StatusCode::NOT_MODIFIED {
             warn!(
                 target: "github_client",
                 %status,
                 endpoint = %path,
                 "Request failed"
             );
-        } else {
-            info!(
-                target: "github_client",
-                %status,
-                endpoint = %path,
-                "Request successful"
-            );
+            return Err(GitHubError::from_response(response).await);
         }
 
-        response.error_for_status()
+        info!(
+            target: "github_client",
+            %status,
+            endpoint = %path,
+            "Request successful"
+        );
+        Ok(response)
+    }
+
+    /// 認証済みユーザーのリポジトリを全ページ分取得する
+    pub async fn get_user_repos(&self) -> Result<Vec<Repository>, GitHubError> {
+        self.collect_all("/user/repos", Some(100)).await
     }
 
-    pub async fn post<T: serde::Serialize>(
-        &self,
-        path: &str,
-        body: &T,
-    ) -> reqwest::Result<Response> {
-        let url = format!("{}{}", self.base_url, path);
-        let headers = build_auth_headers(self.token.as_str());
-        self.http.post(url).headers(headers).json(body).send().await
+    /// 認証済みユーザーを取得する
+    pub async fn get_authenticated_user(&self) -> Result<User, GitHubError> {
+        Ok(self.get("/user").await?.json().await?)
     }
 
-    pub async fn patch<T: serde::Serialize>(
+    /// ブランチのリファレンスを取得する
+    pub async fn get_branch_ref(
         &self,
-        path: &str,
-        body: &T,
-    ) -> reqwest::Result<Response> {
-        let url = format!("{}{}", self.base_url, path);
-        let headers = build_auth_headers(self.token.as_str());
-        self.http
-            .patch(url)
-            .headers(headers)
-            .json(body)
-            .send()
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<GitRef, GitHubError> {
+        self.get_ref(owner, repo, &format!("heads/{}", branch))
             .await
     }
 
-    // Example API method using the generic request methods
-    pub async fn get_user_repos(&self) -> reqwest::Result<Response> {
-        self.get("/user/repos").await
-    }
-
     /// Get the latest commit SHA of a base branch
     pub async fn get_base_branch_sha(
         &self,
@@ -120,29 +548,8 @@ impl GitHubClient {
         repo: &str,
         base_branch: &str,
     ) -> Result<String, GitHubError> {
-        let path = format!("/repos/{}/{}/git/ref/heads/{}", owner, repo, base_branch);
-        let response = self.get(&path).await?;
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
-        }
-
-        let json: Value = response.json().await?;
-
-        // Extract the SHA from the response JSON
-        json.get("object")
-            .and_then(|obj| obj.get("sha"))
-            .and_then(|sha| sha.as_str())
-            .map(String::from)
-            .ok_or_else(|| {
-                GitHubError::ParseError("Failed to extract SHA from response".to_string())
-            })
+        let git_ref = self.get_branch_ref(owner, repo, base_branch).await?;
+        Ok(git_ref.object.sha)
     }
 
     /// Create a new branch using a base SHA
@@ -152,25 +559,26 @@ impl GitHubClient {
         repo: &str,
         new_branch_name: &str,
         base_sha: &str,
-    ) -> Result<(), GitHubError> {
-        let path = format!("/repos/{}/{}/git/refs", owner, repo);
-        let body = serde_json::json!({
-            "ref": format!("refs/heads/{}", new_branch_name),
-            "sha": base_sha
-        });
-
-        let response = self.post(&path, &body).await?;
-        let status = response.status();
+    ) -> Result<GitRef, GitHubError> {
+        check_branch_name(new_branch_name)?;
+        self.create_ref(
+            owner,
+            repo,
+            &format!("refs/heads/{}", new_branch_name),
+            base_sha,
+        )
+        .await
+    }
 
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
-        }
-        Ok(())
+    /// コミットを取得する
+    pub async fn get_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        commit_sha: &str,
+    ) -> Result<Commit, GitHubError> {
+        let path = format!("/repos/{}/{}/git/commits/{}", owner, repo, commit_sha);
+        Ok(self.get(&path).await?.json().await?)
     }
 
     /// 最新のコミットのツリーSHAを取得する
@@ -180,27 +588,8 @@ impl GitHubClient {
         repo: &str,
         commit_sha: &str,
     ) -> Result<String, GitHubError> {
-        let path = format!("/repos/{}/{}/git/commits/{}", owner, repo, commit_sha);
-        let response = self.get(&path).await?;
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
-        }
-
-        let json: Value = response.json().await?;
-        json.get("tree")
-            .and_then(|tree| tree.get("sha"))
-            .and_then(|sha| sha.as_str())
-            .map(String::from)
-            .ok_or_else(|| {
-                GitHubError::ParseError("Failed to extract tree SHA from response".to_string())
-            })
+        let commit = self.get_commit(owner, repo, commit_sha).await?;
+        Ok(commit.tree.sha)
     }
 
     /// ファイル内容のBLOBを作成する
@@ -209,32 +598,14 @@ impl GitHubClient {
         owner: &str,
         repo: &str,
         content: &str,
-    ) -> Result<String, GitHubError> {
+    ) -> Result<Blob, GitHubError> {
         let path = format!("/repos/{}/{}/git/blobs", owner, repo);
         let body = serde_json::json!({
             "content": content,
             "encoding": "utf-8"
         });
 
-        let response = self.post(&path, &body).await?;
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
-        }
-
-        let json: Value = response.json().await?;
-        json.get("sha")
-            .and_then(|sha| sha.as_str())
-            .map(String::from)
-            .ok_or_else(|| {
-                GitHubError::ParseError("Failed to extract blob SHA from response".to_string())
-            })
+        Ok(self.post_idempotent(&path, &body).await?.json().await?)
     }
 
     /// BLOBを含むツリーを作成する
@@ -245,37 +616,33 @@ impl GitHubClient {
         base_tree: &str,
         path: &str,
         blob_sha: &str,
-    ) -> Result<String, GitHubError> {
-        let api_path = format!("/repos/{}/{}/git/trees", owner, repo);
-        let body = serde_json::json!({
-            "base_tree": base_tree,
-            "tree": [{
-                "path": path,
-                "mode": "100644",
-                "type": "blob",
-                "sha": blob_sha
-            }]
-        });
-
-        let response = self.post(&api_path, &body).await?;
-        let status = response.status();
+    ) -> Result<Tree, GitHubError> {
+        self.create_tree_with_entries(
+            owner,
+            repo,
+            Some(base_tree),
+            &[NewTreeEntry::file(path, blob_sha)],
+        )
+        .await
+    }
 
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
+    /// 複数のエントリを含むツリーを作成する
+    ///
+    /// `base_tree` を指定すると、そのツリーに対する差分としてエントリが適用される。
+    pub async fn create_tree_with_entries(
+        &self,
+        owner: &str,
+        repo: &str,
+        base_tree: Option<&str>,
+        entries: &[NewTreeEntry],
+    ) -> Result<Tree, GitHubError> {
+        let api_path = format!("/repos/{}/{}/git/trees", owner, repo);
+        let mut body = serde_json::json!({ "tree": entries });
+        if let Some(base_tree) = base_tree {
+            body["base_tree"] = serde_json::Value::from(base_tree);
         }
 
-        let json: Value = response.json().await?;
-        json.get("sha")
-            .and_then(|sha| sha.as_str())
-            .map(String::from)
-            .ok_or_else(|| {
-                GitHubError::ParseError("Failed to extract tree SHA from response".to_string())
-            })
+        Ok(self.post_idempotent(&api_path, &body).await?.json().await?)
     }
 
     /// 新しいコミットを作成する
@@ -286,62 +653,52 @@ impl GitHubClient {
         message: &str,
         tree_sha: &str,
         parent_sha: &str,
-    ) -> Result<String, GitHubError> {
-        let path = format!("/repos/{}/{}/git/commits", owner, repo);
-        let body = serde_json::json!({
-            "message": message,
-            "tree": tree_sha,
-            "parents": [parent_sha]
-        });
-
-        let response = self.post(&path, &body).await?;
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
-        }
+    ) -> Result<Commit, GitHubError> {
+        let commit = NewCommit::new(message, tree_sha).parent(parent_sha);
+        self.create_commit_with(owner, repo, &commit).await
+    }
 
-        let json: Value = response.json().await?;
-        json.get("sha")
-            .and_then(|sha| sha.as_str())
-            .map(String::from)
-            .ok_or_else(|| {
-                GitHubError::ParseError("Failed to extract commit SHA from response".to_string())
-            })
+    /// 作者・コミッター・複数の親・署名を指定してコミットを作成する
+    pub async fn create_commit_with(
+        &self,
+        owner: &str,
+        repo: &str,
+        commit: &NewCommit,
+    ) -> Result<Commit, GitHubError> {
+        let path = format!("/repos/{}/{}/git/commits", owner, repo);
+        Ok(self.post_idempotent(&path, commit).await?.json().await?)
     }
 
     /// ブランチの先端を更新する
+    ///
+    /// fast-forward でない更新は拒否される（`GitHubError::is_non_fast_forward`）。
     pub async fn update_branch_reference(
         &self,
         owner: &str,
         repo: &str,
         branch: &str,
         commit_sha: &str,
-    ) -> Result<(), GitHubError> {
-        let path = format!("/repos/{}/{}/git/refs/heads/{}", owner, repo, branch);
-        let body = serde_json::json!({
-            "sha": commit_sha,
-            "force": false
-        });
-
-        let response = self.patch(&path, &body).await?;
-        let status = response.status();
+    ) -> Result<GitRef, GitHubError> {
+        self.update_branch_reference_with(owner, repo, branch, commit_sha, false)
+            .await
+    }
 
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
-        }
+    /// ブランチの先端を更新する（`force` が `true` なら fast-forward でなくても上書きする）
+    pub async fn update_branch_reference_with(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        commit_sha: &str,
+        force: bool,
+    ) -> Result<GitRef, GitHubError> {
+        self.update_ref(owner, repo, &format!("heads/{}", branch), commit_sha, force)
+            .await
+    }
 
-        Ok(())
+    /// 複数ファイルをまとめてコミットするビルダーを作成する
+    pub fn commit_builder(&self, owner: &str, repo: &str, branch: &str) -> CommitBuilder<'_> {
+        CommitBuilder::new(self, owner, repo, branch)
     }
 
     /// プルリクエストを作成する
@@ -355,7 +712,7 @@ impl GitHubClient {
     /// * `body` - プルリクエストの説明文
     ///
     /// # 戻り値
-    /// * `Ok(())` - プルリクエストの作成に成功
+    /// * `Ok(PullRequest)` - 作成されたプルリクエスト
     /// * `Err(GitHubError)` - APIリクエストが失敗した場合のエラー
     pub async fn create_pull_request(
         &self,
@@ -365,7 +722,7 @@ impl GitHubClient {
         head: &str,
         title: &str,
         body: &str,
-    ) -> Result<(), GitHubError> {
+    ) -> Result<PullRequest, GitHubError> {
         let path = format!("/repos/{}/{}/pulls", owner, repo);
         let request_body = serde_json::json!({
             "title": title,
@@ -374,18 +731,7 @@ impl GitHubClient {
             "head": head
         });
 
-        let response = self.post(&path, &request_body).await?;
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_json: Value = response.json().await?;
-            let message = error_json["message"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(GitHubError::ApiError { status, message });
-        }
-        Ok(())
+        Ok(self.post(&path, &request_body).await?.json().await?)
     }
 }
 
@@ -393,20 +739,146 @@ impl GitHubClient {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_github_client_creation() {
+    #[tokio::test]
+    async fn test_github_client_creation() {
         let token = "test_token".to_string();
         let client = GitHubClient::new(token.clone());
-        assert_eq!(client.token.as_str(), token);
+        assert_eq!(client.credentials.token().await.unwrap().as_str(), token);
         assert_eq!(client.base_url, "https://api.github.com");
     }
 
+    #[test]
+    fn test_builder_configuration() {
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .enterprise("github.example.com/")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "https://github.example.com/api/v3");
+        assert_eq!(
+            client.upload_url(),
+            "https://github.example.com/api/uploads"
+        );
+
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url("http://localhost:8080/")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "http://localhost:8080");
+        assert_eq!(client.upload_url(), DEFAULT_UPLOAD_URL);
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let error = GitHubClient::builder().build().err().unwrap();
+        assert!(matches!(error, GitHubError::ConfigError(_)));
+
+        let error = GitHubClient::builder()
+            .token("test_token")
+            .default_header("x-custom", "bad\nvalue")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(error, GitHubError::ConfigError(_)));
+
+        let error = GitHubClient::builder()
+            .token("test_token")
+            .base_url("api.github.com")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(error, GitHubError::ConfigError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_builder_default_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/user")
+            .match_header("user-agent", "tachyon-bot/1.0")
+            .match_header("x-github-api-version", DEFAULT_API_VERSION)
+            .match_header("x-request-source", "tests")
+            .with_status(200)
+            .with_body(r#"{"login": "octocat", "id": 1, "node_id": "MDQ6VXNlcjE="}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::builder()
+            .token("test_token")
+            .base_url(server.url())
+            .user_agent("tachyon-bot/1.0")
+            .default_header("x-request-source", "tests")
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        client.get_authenticated_user().await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_credentials_are_refreshed_after_unauthorized() {
+        use crate::auth::credentials::CredentialProvider;
+        use async_trait::async_trait;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Rotating(AtomicUsize);
+
+        #[async_trait]
+        impl CredentialProvider for Rotating {
+            async fn token(&self) -> Result<AuthToken, GitHubError> {
+                Ok(AuthToken::new(format!(
+                    "token-{}",
+                    self.0.load(Ordering::SeqCst)
+                )))
+            }
+
+            async fn invalidate(&self) -> bool {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        }
+
+        let mut server = mockito::Server::new_async().await;
+        let rejected = server
+            .mock("GET", "/user")
+            .match_header("authorization", "token token-0")
+            .with_status(401)
+            .with_body(r#"{"message": "Bad credentials"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("GET", "/user")
+            .match_header("authorization", "token token-1")
+            .with_status(200)
+            .with_body(r#"{"login": "octocat", "id": 1, "node_id": "MDQ6VXNlcjE="}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::builder()
+            .credentials(Rotating(AtomicUsize::new(0)))
+            .base_url(server.url())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            client.get_authenticated_user().await.unwrap().login,
+            "octocat"
+        );
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_get_base_branch_sha() {
         use serde_json::json;
 
         let mock_response = json!({
             "ref": "refs/heads/main",
+            "url": "https://api.github.com/repos/octocat/Hello-World/git/refs/heads/main",
             "object": {
                 "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e",
                 "type": "commit",
@@ -445,7 +917,7 @@ mod tests {
             .match_body(mockito::Matcher::Json(expected_body))
             .with_status(201)
             .with_header("content-type", "application/json")
-            .with_body(r#"{"ref": "refs/heads/new-feature", "object": {"sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e"}}"#)
+            .with_body(r#"{"ref": "refs/heads/new-feature", "url": "https://api.github.com/repos/owner/repo/git/refs/heads/new-feature", "object": {"sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e", "type": "commit", "url": "https://api.github.com/repos/owner/repo/git/commits/6dcb09b5b57875f334f61aebed695e2e4193db5e"}}"#)
             .create_async()
             .await;
 
@@ -461,7 +933,12 @@ mod tests {
             )
             .await;
 
-        assert!(result.is_ok());
+        let git_ref = result.unwrap();
+        assert_eq!(git_ref.ref_name, "refs/heads/new-feature");
+        assert_eq!(
+            git_ref.object.sha,
+            "6dcb09b5b57875f334f61aebed695e2e4193db5e"
+        );
     }
 
     #[tokio::test]
@@ -476,11 +953,26 @@ mod tests {
         });
 
         let mut server = mockito::Server::new_async().await;
-        let _mock = server.mock("POST", "/repos/owner/repo/pulls")
+        let _mock = server
+            .mock("POST", "/repos/owner/repo/pulls")
             .match_body(mockito::Matcher::Json(expected_body))
             .with_status(201)
             .with_header("content-type", "application/json")
-            .with_body(r#"{"number": 1, "state": "open"}"#)
+            .with_body(
+                json!({
+                    "id": 1,
+                    "node_id": "PR_kwDOA",
+                    "number": 1,
+                    "state": "open",
+                    "title": "テスト PR",
+                    "body": "PR の本文",
+                    "url": "https://api.github.com/repos/owner/repo/pulls/1",
+                    "html_url": "https://github.com/owner/repo/pull/1",
+                    "head": {"label": "owner:feature-branch", "ref": "feature-branch", "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e"},
+                    "base": {"label": "owner:main", "ref": "main", "sha": "1acc419d4d6a9ce985db7be48c6349a0475975b5"}
+                })
+                .to_string(),
+            )
             .create_async()
             .await;
 
@@ -498,6 +990,53 @@ mod tests {
             )
             .await;
 
-        assert!(result.is_ok());
+        let pr = result.unwrap();
+        assert_eq!(pr.number, 1);
+        assert_eq!(pr.html_url, "https://github.com/owner/repo/pull/1");
+        assert_eq!(pr.head.ref_name, "feature-branch");
+        assert_eq!(pr.base.ref_name, "main");
+    }
+
+    #[tokio::test]
+    async fn test_error_responses_are_mapped() {
+        let mut server = mockito::Server::new_async().await;
+        let _not_found = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/missing")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"message": "Not Found", "documentation_url": "https://docs.github.com/rest"}"#,
+            )
+            .create_async()
+            .await;
+        let _unprocessable = server
+            .mock("POST", "/repos/owner/repo/pulls")
+            .with_status(422)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"message": "Validation Failed", "errors": [{"resource": "PullRequest", "field": "head", "code": "invalid"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let error = client
+            .get_base_branch_sha("owner", "repo", "missing")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::NotFoundError(_)));
+
+        let error = client
+            .create_pull_request("owner", "repo", "main", "nope", "title", "body")
+            .await
+            .unwrap_err();
+        match error {
+            GitHubError::ValidationError { errors, .. } => {
+                assert_eq!(errors[0].field.as_deref(), Some("head"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
     }
 }
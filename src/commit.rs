@@ -0,0 +1,1075 @@
+use crate::client::GitHubClient;
+use crate::errors::GitHubError;
+use crate::models::{FileMode, GitUser, NewCommit, NewTreeEntry, ObjectType, TreeEntry};
+use crate::patch::DEFAULT_MAX_FUZZ;
+use crate::signing::CommitSigner;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use tracing::{debug, info, warn};
+
+const CREATE_COMMIT_ON_BRANCH: &str = "mutation($input: CreateCommitOnBranchInput!) {
+  createCommitOnBranch(input: $input) {
+    commit { oid }
+  }
+}";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateCommitOnBranchData {
+    create_commit_on_branch: CreateCommitOnBranchPayload,
+}
+
+#[derive(Deserialize)]
+struct CreateCommitOnBranchPayload {
+    commit: CommitOid,
+}
+
+#[derive(Deserialize)]
+struct CommitOid {
+    oid: String,
+}
+
+/// コミットに含めるファイル変更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    /// ファイルを追加、または既存ファイルを上書きする
+    ///
+    /// `mode` には `File`、`Executable`、`Symlink`（内容はリンク先パス）を指定する。
+    Write {
+        path: String,
+        content: Vec<u8>,
+        mode: FileMode,
+    },
+    /// サブモジュールを指定コミットに固定する
+    Submodule { path: String, commit_sha: String },
+    /// ファイルを削除する
+    Delete { path: String },
+    /// ファイルの内容を保ったままパスを変更する
+    Rename { from: String, to: String },
+}
+
+impl FileChange {
+    /// 変更後にファイルが存在するパス（削除の場合は削除されるパス）
+    pub fn path(&self) -> &str {
+        match self {
+            FileChange::Write { path, .. }
+            | FileChange::Submodule { path, .. }
+            | FileChange::Delete { path } => path,
+            FileChange::Rename { to, .. } => to,
+        }
+    }
+}
+
+/// 複数ファイルの変更を1つのコミットにまとめてブランチへ反映するビルダー
+///
+/// BLOB作成、`base_tree` を使ったツリー作成、コミット作成、ブランチ更新を
+/// `commit()` の1回の呼び出しで行う。
+///
+/// ```no_run
+/// # async fn run(client: &github::client::GitHubClient) -> Result<(), github::errors::GitHubError> {
+/// let sha = client
+///     .commit_builder("owner", "repo", "feature")
+///     .message("Update docs")
+///     .add_file("docs/a.md", "# A")
+///     .add_file("docs/b.md", "# B")
+///     .delete_file("docs/old.md")
+///     .rename_file("docs/draft.md", "docs/final.md")
+///     .commit()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct CommitBuilder<'a> {
+    pub(crate) client: &'a GitHubClient,
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) branch: String,
+    pub(crate) message: Option<String>,
+    author: Option<GitUser>,
+    committer: Option<GitUser>,
+    pub(crate) expected_head: Option<String>,
+    signer: Option<&'a dyn CommitSigner>,
+    pub(crate) max_retries: u32,
+    force: bool,
+    pub(crate) max_fuzz: usize,
+    pub(crate) existing_blobs: HashSet<String>,
+    changes: Vec<FileChange>,
+}
+
+impl<'a> CommitBuilder<'a> {
+    pub fn new(client: &'a GitHubClient, owner: &str, repo: &str, branch: &str) -> Self {
+        Self {
+            client,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            branch: branch.to_string(),
+            message: None,
+            author: None,
+            committer: None,
+            expected_head: None,
+            signer: None,
+            max_retries: 0,
+            force: false,
+            max_fuzz: DEFAULT_MAX_FUZZ,
+            existing_blobs: HashSet::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// コミットメッセージを指定する
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// 作者を指定する（省略時は認証ユーザー）
+    pub fn author(mut self, author: GitUser) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// コミッターを指定する（省略時は作者と同じ）
+    pub fn committer(mut self, committer: GitUser) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    /// 変更の元にするブランチの先頭コミット
+    ///
+    /// 指定するとブランチを読み直さずにこのコミットを親にする。ブランチがその後に
+    /// 更新されていた場合、ブランチの更新は失敗する。
+    pub fn expected_head(mut self, sha: impl Into<String>) -> Self {
+        self.expected_head = Some(sha.into());
+        self
+    }
+
+    /// ブランチが先に更新されていた場合に、変更を新しい先頭へ適用し直す回数（`commit()` のみ）
+    ///
+    /// 既定は0で、fast-forward でない更新はそのままエラーになる。再試行時は
+    /// `expected_head` を指定していてもブランチの最新の先頭を読み直す。
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// fast-forward でなくてもブランチを上書きする（`commit()` のみ）
+    ///
+    /// 間に行われたプッシュは失われるため、上書きが意図したものである場合にだけ使う。
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// リポジトリに既にあることがわかっている BLOB の SHA
+    ///
+    /// 同じ内容のファイルはアップロードせずにこの BLOB を使う。
+    pub fn existing_blobs<I, S>(mut self, shas: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.existing_blobs.extend(shas.into_iter().map(Into::into));
+        self
+    }
+
+    /// ローカルの鍵でコミットに署名する（作者の指定が必要）
+    pub fn sign_with(mut self, signer: &'a dyn CommitSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// ファイルを追加する（既存ファイルの場合は内容を更新する）
+    ///
+    /// 内容は base64 で送信されるため、バイナリファイルも扱える。
+    pub fn add_file(self, path: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+        self.write(path, content, FileMode::File)
+    }
+
+    /// 実行可能ファイル（`100755`）を追加・更新する
+    pub fn add_executable(self, path: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+        self.write(path, content, FileMode::Executable)
+    }
+
+    /// `target` を指すシンボリックリンクを作成する
+    pub fn add_symlink(self, path: impl Into<String>, target: impl Into<String>) -> Self {
+        self.write(path, target.into(), FileMode::Symlink)
+    }
+
+    /// サブモジュールを `commit_sha` に固定する
+    pub fn set_submodule(mut self, path: impl Into<String>, commit_sha: impl Into<String>) -> Self {
+        self.changes.push(FileChange::Submodule {
+            path: path.into(),
+            commit_sha: commit_sha.into(),
+        });
+        self
+    }
+
+    fn write(
+        mut self,
+        path: impl Into<String>,
+        content: impl Into<Vec<u8>>,
+        mode: FileMode,
+    ) -> Self {
+        self.changes.push(FileChange::Write {
+            path: path.into(),
+            content: content.into(),
+            mode,
+        });
+        self
+    }
+
+    /// ファイルを削除する
+    pub fn delete_file(mut self, path: impl Into<String>) -> Self {
+        self.changes.push(FileChange::Delete { path: path.into() });
+        self
+    }
+
+    /// ファイルをリネームする
+    pub fn rename_file(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.changes.push(FileChange::Rename {
+            from: from.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// 任意の変更を追加する
+    pub fn change(mut self, change: FileChange) -> Self {
+        self.changes.push(change);
+        self
+    }
+
+    fn validate(&self) -> Result<&str, GitHubError> {
+        let message = self.message.as_deref().ok_or_else(|| {
+            GitHubError::InvalidRequestError("Commit message is required".to_string())
+        })?;
+        if self.changes.is_empty() {
+            return Err(GitHubError::InvalidRequestError(
+                "No file changes to commit".to_string(),
+            ));
+        }
+        Ok(message)
+    }
+
+    pub(crate) async fn head_sha(&self) -> Result<String, GitHubError> {
+        match &self.expected_head {
+            Some(sha) => Ok(sha.clone()),
+            None => {
+                self.client
+                    .get_base_branch_sha(&self.owner, &self.repo, &self.branch)
+                    .await
+            }
+        }
+    }
+
+    /// 変更をコミットしてブランチを更新し、新しいコミットSHAを返す
+    ///
+    /// `max_retries` を指定すると、ブランチの更新が fast-forward でないために
+    /// 拒否された場合に先頭を読み直し、同じ変更をその上に適用して再試行する。
+    pub async fn commit(self) -> Result<String, GitHubError> {
+        let message = self.validate()?;
+        let (owner, repo, branch) = (&self.owner, &self.repo, &self.branch);
+        info!(
+            target: "github_client",
+            %owner,
+            %repo,
+            %branch,
+            changes = self.changes.len(),
+            "Creating multi-file commit"
+        );
+
+        let mut uploaded = self.existing_blobs.clone();
+        let mut parent_sha = self.head_sha().await?;
+        let mut attempt = 0;
+        loop {
+            let sha = self
+                .create_commit_on(message, &parent_sha, &mut uploaded)
+                .await?;
+            match self
+                .client
+                .update_branch_reference_with(owner, repo, branch, &sha, self.force)
+                .await
+            {
+                Ok(_) => {
+                    info!(target: "github_client", %sha, "Multi-file commit created");
+                    return Ok(sha);
+                }
+                Err(e) if e.is_non_fast_forward() && attempt < self.max_retries => {
+                    attempt += 1;
+                    parent_sha = self.client.get_base_branch_sha(owner, repo, branch).await?;
+                    warn!(
+                        target: "github_client",
+                        %branch,
+                        attempt,
+                        head = %parent_sha,
+                        "Branch moved; reapplying changes on the new head"
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// `parent_sha` の上に変更を適用したコミットを作成する（ブランチは更新しない）
+    async fn create_commit_on(
+        &self,
+        message: &str,
+        parent_sha: &str,
+        uploaded: &mut HashSet<String>,
+    ) -> Result<String, GitHubError> {
+        let (owner, repo) = (&self.owner, &self.repo);
+        let base_tree_sha = self
+            .client
+            .get_latest_tree_sha(owner, repo, parent_sha)
+            .await?;
+
+        let entries = self.tree_entries(&base_tree_sha, uploaded).await?;
+        let tree = self
+            .client
+            .create_tree_with_entries(owner, repo, Some(&base_tree_sha), &entries)
+            .await?;
+        let mut new_commit = NewCommit::new(message, &tree.sha).parent(parent_sha);
+        new_commit.author = self.author.clone();
+        new_commit.committer = self.committer.clone();
+        let commit = match self.signer {
+            Some(signer) => {
+                self.client
+                    .create_signed_commit(owner, repo, new_commit, signer)
+                    .await?
+            }
+            None => {
+                self.client
+                    .create_commit_with(owner, repo, &new_commit)
+                    .await?
+            }
+        };
+        Ok(commit.sha)
+    }
+
+    /// GraphQL の `createCommitOnBranch` でコミットし、新しいコミットSHAを返す
+    ///
+    /// コミットは GitHub によって署名されるため、App のトークンで作成しても "Verified" になる。
+    /// `expectedHeadOid` にはブランチの先頭（または `expected_head`）を渡し、その間に
+    /// 別のプッシュがあれば GitHub がコミットを拒否する。
+    ///
+    /// このミューテーションはファイルモードを指定できないため、実行可能ファイル・
+    /// シンボリックリンク・サブモジュールの変更は `InvalidRequestError` になる。
+    /// 作者・コミッターも認証ユーザーに固定されるため指定できず、`sign_with` も使えない。
+    pub async fn commit_on_branch(self) -> Result<String, GitHubError> {
+        let message = self.validate()?;
+        if self.author.is_some() || self.committer.is_some() {
+            return Err(GitHubError::InvalidRequestError(
+                "createCommitOnBranch does not accept an author or committer".to_string(),
+            ));
+        }
+        if self.signer.is_some() {
+            return Err(GitHubError::InvalidRequestError(
+                "createCommitOnBranch commits are signed by GitHub".to_string(),
+            ));
+        }
+        if self.force {
+            return Err(GitHubError::InvalidRequestError(
+                "createCommitOnBranch cannot force-update a branch".to_string(),
+            ));
+        }
+        let (owner, repo, branch) = (&self.owner, &self.repo, &self.branch);
+        info!(
+            target: "github_client",
+            %owner,
+            %repo,
+            %branch,
+            changes = self.changes.len(),
+            "Creating commit with createCommitOnBranch"
+        );
+
+        let head_sha = self.head_sha().await?;
+        let file_changes = self.graphql_file_changes(&head_sha).await?;
+        let (headline, body) = match message.split_once('\n') {
+            Some((headline, body)) => (headline.trim_end(), body.trim()),
+            None => (message, ""),
+        };
+        let mut commit_message = json!({ "headline": headline });
+        if !body.is_empty() {
+            commit_message["body"] = body.into();
+        }
+        let variables = json!({
+            "input": {
+                "branch": {
+                    "repositoryNameWithOwner": format!("{}/{}", owner, repo),
+                    "branchName": branch,
+                },
+                "expectedHeadOid": head_sha,
+                "message": commit_message,
+                "fileChanges": file_changes,
+            }
+        });
+
+        let data: CreateCommitOnBranchData = self
+            .client
+            .graphql(CREATE_COMMIT_ON_BRANCH, &variables)
+            .await?;
+        let sha = data.create_commit_on_branch.commit.oid;
+        info!(target: "github_client", %sha, "Commit created with createCommitOnBranch");
+        Ok(sha)
+    }
+
+    /// 変更内容を `FileChanges` 入力に変換する
+    async fn graphql_file_changes(&self, head_sha: &str) -> Result<Value, GitHubError> {
+        let (owner, repo) = (&self.owner, &self.repo);
+        let mut additions = Vec::new();
+        let mut deletions = Vec::new();
+        let mut base_tree_sha = None;
+
+        for change in &self.changes {
+            match change {
+                FileChange::Write {
+                    path,
+                    content,
+                    mode: FileMode::File,
+                } => {
+                    additions.push(json!({"path": path, "contents": STANDARD.encode(content)}));
+                }
+                FileChange::Delete { path } => deletions.push(json!({ "path": path })),
+                FileChange::Rename { from, to } => {
+                    let tree_sha = match &base_tree_sha {
+                        Some(sha) => sha,
+                        None => base_tree_sha.insert(
+                            self.client
+                                .get_latest_tree_sha(owner, repo, head_sha)
+                                .await?,
+                        ),
+                    };
+                    let source = find_entry(self.client, owner, repo, tree_sha, from)
+                        .await?
+                        .ok_or_else(|| {
+                            GitHubError::NotFoundError(format!(
+                                "{} does not exist on {}",
+                                from, self.branch
+                            ))
+                        })?;
+                    if source.mode != FileMode::File {
+                        return Err(GitHubError::InvalidRequestError(format!(
+                            "createCommitOnBranch cannot preserve the mode of {}",
+                            from
+                        )));
+                    }
+                    let content = self
+                        .client
+                        .get_blob_content(owner, repo, &source.sha)
+                        .await?;
+                    deletions.push(json!({ "path": from }));
+                    additions.push(json!({"path": to, "contents": STANDARD.encode(content)}));
+                }
+                FileChange::Write { path, .. } | FileChange::Submodule { path, .. } => {
+                    return Err(GitHubError::InvalidRequestError(format!(
+                        "createCommitOnBranch cannot set the file mode of {}",
+                        path
+                    )));
+                }
+            }
+        }
+
+        Ok(json!({ "additions": additions, "deletions": deletions }))
+    }
+
+    /// 変更内容をツリーエントリに変換する（必要なBLOBはここで作成する）
+    ///
+    /// `uploaded` には作成済み・既存の BLOB が記録され、再試行時に再アップロードしない。
+    async fn tree_entries(
+        &self,
+        base_tree_sha: &str,
+        uploaded: &mut HashSet<String>,
+    ) -> Result<Vec<NewTreeEntry>, GitHubError> {
+        let (owner, repo) = (&self.owner, &self.repo);
+        let mut entries = Vec::with_capacity(self.changes.len());
+
+        for change in &self.changes {
+            match change {
+                FileChange::Write {
+                    path,
+                    content,
+                    mode,
+                } => {
+                    let blob = self
+                        .client
+                        .create_blob_if_missing(owner, repo, content, uploaded)
+                        .await?;
+                    debug!(target: "github_client", %path, sha = %blob.sha, "Blob created");
+                    uploaded.insert(blob.sha.clone());
+                    entries.push(NewTreeEntry::new(path, *mode, blob.sha));
+                }
+                FileChange::Submodule { path, commit_sha } => {
+                    entries.push(NewTreeEntry::submodule(path, commit_sha));
+                }
+                FileChange::Delete { path } => {
+                    entries.push(NewTreeEntry::delete(path));
+                }
+                FileChange::Rename { from, to } => {
+                    let source = find_entry(self.client, owner, repo, base_tree_sha, from)
+                        .await?
+                        .ok_or_else(|| {
+                            GitHubError::NotFoundError(format!(
+                                "{} does not exist on {}",
+                                from, self.branch
+                            ))
+                        })?;
+                    entries.push(NewTreeEntry::delete(from));
+                    entries.push(NewTreeEntry::new(to, source.mode, source.sha));
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+/// ツリーを上から辿って `path` のエントリを探す
+pub(crate) async fn find_entry(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    tree_sha: &str,
+    path: &str,
+) -> Result<Option<TreeEntry>, GitHubError> {
+    let mut current = tree_sha.to_string();
+    let mut components = path.trim_matches('/').split('/').peekable();
+
+    while let Some(name) = components.next() {
+        let tree = client.get_tree(owner, repo, &current, false).await?;
+        let Some(entry) = tree.tree.into_iter().find(|e| e.path == name) else {
+            return Ok(None);
+        };
+        if components.peek().is_none() {
+            return Ok(Some(TreeEntry {
+                path: path.to_string(),
+                ..entry
+            }));
+        }
+        if entry.entry_type != ObjectType::Tree {
+            return Ok(None);
+        }
+        current = entry.sha;
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    const PARENT_SHA: &str = "6dcb09b5b57875f334f61aebed695e2e4193db5e";
+    const BASE_TREE_SHA: &str = "691272480426f78a0138979dd3ce63b77f706feb";
+    // `printf A | git hash-object --stdin`
+    const BLOB_A: &str = "8c7e5a667f1b771847fe88c01c3de34413a1b220";
+
+    fn commit_json(sha: &str, tree_sha: &str) -> String {
+        json!({
+            "sha": sha,
+            "url": format!("https://api.github.com/repos/owner/repo/git/commits/{}", sha),
+            "message": "message",
+            "tree": {"sha": tree_sha, "url": "https://api.github.com/repos/owner/repo/git/trees/x"},
+            "parents": []
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_commit_builder_creates_single_commit() {
+        let mut server = mockito::Server::new_async().await;
+        let _ref = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/feature")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "ref": "refs/heads/feature",
+                    "url": "https://api.github.com/repos/owner/repo/git/refs/heads/feature",
+                    "object": {"sha": PARENT_SHA, "type": "commit", "url": "https://api.github.com/x"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _parent = server
+            .mock(
+                "GET",
+                format!("/repos/owner/repo/git/commits/{}", PARENT_SHA).as_str(),
+            )
+            .with_status(200)
+            .with_body(commit_json(PARENT_SHA, BASE_TREE_SHA))
+            .create_async()
+            .await;
+        let _root_tree = server
+            .mock(
+                "GET",
+                format!("/repos/owner/repo/git/trees/{}", BASE_TREE_SHA).as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                json!({
+                    "sha": BASE_TREE_SHA,
+                    "url": "https://api.github.com/x",
+                    "tree": [
+                        {"path": "docs", "mode": "040000", "type": "tree", "sha": "d0c5"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _docs_tree = server
+            .mock("GET", "/repos/owner/repo/git/trees/d0c5")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "sha": "d0c5",
+                    "url": "https://api.github.com/x",
+                    "tree": [
+                        {"path": "draft.md", "mode": "100755", "type": "blob", "sha": "dra7"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let blobs = server
+            .mock("POST", "/repos/owner/repo/git/blobs")
+            .with_status(201)
+            .with_body(r#"{"sha": "b10b", "url": "https://api.github.com/x"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let tree = server
+            .mock("POST", "/repos/owner/repo/git/trees")
+            .match_body(Matcher::Json(json!({
+                "base_tree": BASE_TREE_SHA,
+                "tree": [
+                    {"path": "a.txt", "mode": "100644", "type": "blob", "sha": "b10b"},
+                    {"path": "b.txt", "mode": "100755", "type": "blob", "sha": "b10b"},
+                    {"path": "vendor/lib", "mode": "160000", "type": "commit", "sha": "5ub"},
+                    {"path": "old.txt", "mode": "100644", "type": "blob", "sha": null},
+                    {"path": "docs/draft.md", "mode": "100644", "type": "blob", "sha": null},
+                    {"path": "docs/final.md", "mode": "100755", "type": "blob", "sha": "dra7"}
+                ]
+            })))
+            .with_status(201)
+            .with_body(r#"{"sha": "new7ree", "url": "https://api.github.com/x", "tree": []}"#)
+            .create_async()
+            .await;
+        let commit = server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .match_body(Matcher::Json(json!({
+                "message": "Update files",
+                "tree": "new7ree",
+                "parents": [PARENT_SHA]
+            })))
+            .with_status(201)
+            .with_body(commit_json("c0ffee", "new7ree"))
+            .create_async()
+            .await;
+        let update = server
+            .mock("PATCH", "/repos/owner/repo/git/refs/heads/feature")
+            .match_body(Matcher::Json(json!({"sha": "c0ffee", "force": false})))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "ref": "refs/heads/feature",
+                    "url": "https://api.github.com/x",
+                    "object": {"sha": "c0ffee", "type": "commit", "url": "https://api.github.com/x"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let sha = client
+            .commit_builder("owner", "repo", "feature")
+            .message("Update files")
+            .add_file("a.txt", "A")
+            .add_executable("b.txt", "B")
+            .set_submodule("vendor/lib", "5ub")
+            .delete_file("old.txt")
+            .rename_file("docs/draft.md", "docs/final.md")
+            .commit()
+            .await
+            .unwrap();
+
+        assert_eq!(sha, "c0ffee");
+        blobs.assert_async().await;
+        tree.assert_async().await;
+        commit.assert_async().await;
+        update.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_commit_builder_requires_message_and_changes() {
+        let client = GitHubClient::new("test_token".to_string());
+
+        let error = client
+            .commit_builder("owner", "repo", "main")
+            .add_file("a.txt", "A")
+            .commit()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+
+        let error = client
+            .commit_builder("owner", "repo", "main")
+            .message("empty")
+            .commit()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+    }
+
+    fn ref_json(sha: &str) -> String {
+        json!({
+            "ref": "refs/heads/feature",
+            "url": "https://api.github.com/repos/owner/repo/git/refs/heads/feature",
+            "object": {"sha": sha, "type": "commit", "url": "https://api.github.com/x"}
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_commit_skips_existing_blobs() {
+        let mut server = mockito::Server::new_async().await;
+        let _commit = server
+            .mock(
+                "GET",
+                format!("/repos/owner/repo/git/commits/{}", PARENT_SHA).as_str(),
+            )
+            .with_status(200)
+            .with_body(commit_json(PARENT_SHA, BASE_TREE_SHA))
+            .create_async()
+            .await;
+        let blobs = server
+            .mock("POST", "/repos/owner/repo/git/blobs")
+            .expect(0)
+            .create_async()
+            .await;
+        let tree = server
+            .mock("POST", "/repos/owner/repo/git/trees")
+            .match_body(Matcher::Json(json!({
+                "base_tree": BASE_TREE_SHA,
+                "tree": [
+                    {"path": "copy.txt", "mode": "100644", "type": "blob", "sha": BLOB_A}
+                ]
+            })))
+            .with_status(201)
+            .with_body(r#"{"sha": "new7ree", "url": "https://api.github.com/x", "tree": []}"#)
+            .create_async()
+            .await;
+        let _new_commit = server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .with_status(201)
+            .with_body(commit_json("c0ffee", "new7ree"))
+            .create_async()
+            .await;
+        let _update = server
+            .mock("PATCH", "/repos/owner/repo/git/refs/heads/feature")
+            .with_status(200)
+            .with_body(ref_json("c0ffee"))
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let sha = client
+            .commit_builder("owner", "repo", "feature")
+            .message("Copy a.txt")
+            .add_file("copy.txt", "A")
+            .existing_blobs([BLOB_A])
+            .expected_head(PARENT_SHA)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(sha, "c0ffee");
+        blobs.assert_async().await;
+        tree.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_commit_retries_on_non_fast_forward() {
+        const NEW_HEAD: &str = "553c2077f0edc3d5dc5d17262f6aa498e69d6f8e";
+        let mut server = mockito::Server::new_async().await;
+        let _stale_ref = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/feature")
+            .with_status(200)
+            .with_body(ref_json(PARENT_SHA))
+            .expect(1)
+            .create_async()
+            .await;
+        let _fresh_ref = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/feature")
+            .with_status(200)
+            .with_body(ref_json(NEW_HEAD))
+            .create_async()
+            .await;
+        for (sha, tree) in [(PARENT_SHA, BASE_TREE_SHA), (NEW_HEAD, "7ree2")] {
+            server
+                .mock(
+                    "GET",
+                    format!("/repos/owner/repo/git/commits/{}", sha).as_str(),
+                )
+                .with_status(200)
+                .with_body(commit_json(sha, tree))
+                .create_async()
+                .await;
+        }
+        let blobs = server
+            .mock("POST", "/repos/owner/repo/git/blobs")
+            .with_status(201)
+            .with_body(json!({"sha": BLOB_A, "url": "https://api.github.com/x"}).to_string())
+            // 再試行時には作成済みの BLOB を再アップロードしない
+            .expect(2)
+            .create_async()
+            .await;
+        let trees = server
+            .mock("POST", "/repos/owner/repo/git/trees")
+            .with_status(201)
+            .with_body(r#"{"sha": "new7ree", "url": "https://api.github.com/x", "tree": []}"#)
+            .expect(3)
+            .create_async()
+            .await;
+        let _stale_commit = server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .match_body(Matcher::PartialJson(json!({"parents": [PARENT_SHA]})))
+            .with_status(201)
+            .with_body(commit_json("c1", "new7ree"))
+            .create_async()
+            .await;
+        let rebased_commit = server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .match_body(Matcher::PartialJson(json!({"parents": [NEW_HEAD]})))
+            .with_status(201)
+            .with_body(commit_json("c2", "new7ree"))
+            .create_async()
+            .await;
+        let _rejected = server
+            .mock("PATCH", "/repos/owner/repo/git/refs/heads/feature")
+            .match_body(Matcher::PartialJson(json!({"sha": "c1"})))
+            .with_status(422)
+            .with_body(r#"{"message": "Update is not a fast forward"}"#)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("PATCH", "/repos/owner/repo/git/refs/heads/feature")
+            .match_body(Matcher::Json(json!({"sha": "c2", "force": false})))
+            .with_status(200)
+            .with_body(ref_json("c2"))
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let error = client
+            .commit_builder("owner", "repo", "feature")
+            .message("Update")
+            .add_file("a.txt", "A")
+            .expected_head(PARENT_SHA)
+            .commit()
+            .await
+            .unwrap_err();
+        assert!(error.is_non_fast_forward());
+
+        let sha = client
+            .commit_builder("owner", "repo", "feature")
+            .message("Update")
+            .add_file("a.txt", "A")
+            .max_retries(2)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(sha, "c2");
+        rebased_commit.assert_async().await;
+        accepted.assert_async().await;
+        blobs.assert_async().await;
+        trees.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_commit_force_update() {
+        let mut server = mockito::Server::new_async().await;
+        let _commit = server
+            .mock(
+                "GET",
+                format!("/repos/owner/repo/git/commits/{}", PARENT_SHA).as_str(),
+            )
+            .with_status(200)
+            .with_body(commit_json(PARENT_SHA, BASE_TREE_SHA))
+            .create_async()
+            .await;
+        let _tree = server
+            .mock("POST", "/repos/owner/repo/git/trees")
+            .with_status(201)
+            .with_body(r#"{"sha": "new7ree", "url": "https://api.github.com/x", "tree": []}"#)
+            .create_async()
+            .await;
+        let _new_commit = server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .with_status(201)
+            .with_body(commit_json("c0ffee", "new7ree"))
+            .create_async()
+            .await;
+        let update = server
+            .mock("PATCH", "/repos/owner/repo/git/refs/heads/feature")
+            .match_body(Matcher::Json(json!({"sha": "c0ffee", "force": true})))
+            .with_status(200)
+            .with_body(ref_json("c0ffee"))
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let sha = client
+            .commit_builder("owner", "repo", "feature")
+            .message("Reset")
+            .delete_file("a.txt")
+            .expected_head(PARENT_SHA)
+            .force(true)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(sha, "c0ffee");
+        update.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_commit_on_branch() {
+        let mut server = mockito::Server::new_async().await;
+        let _ref = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/feature")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "ref": "refs/heads/feature",
+                    "url": "https://api.github.com/repos/owner/repo/git/refs/heads/feature",
+                    "object": {"sha": PARENT_SHA, "type": "commit", "url": "https://api.github.com/x"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _parent = server
+            .mock(
+                "GET",
+                format!("/repos/owner/repo/git/commits/{}", PARENT_SHA).as_str(),
+            )
+            .with_status(200)
+            .with_body(commit_json(PARENT_SHA, BASE_TREE_SHA))
+            .create_async()
+            .await;
+        let _root_tree = server
+            .mock(
+                "GET",
+                format!("/repos/owner/repo/git/trees/{}", BASE_TREE_SHA).as_str(),
+            )
+            .with_status(200)
+            .with_body(
+                json!({
+                    "sha": BASE_TREE_SHA,
+                    "url": "https://api.github.com/x",
+                    "tree": [
+                        {"path": "draft.md", "mode": "100644", "type": "blob", "sha": "d4af"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _blob = server
+            .mock("GET", "/repos/owner/repo/git/blobs/d4af")
+            .with_status(200)
+            .with_body(
+                json!({"sha": "d4af", "url": "https://api.github.com/x", "content": "RHJh\nZnQ=\n", "encoding": "base64"})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+        let mutation = server
+            .mock("POST", "/graphql")
+            .match_body(Matcher::PartialJson(json!({
+                "variables": {"input": {
+                    "branch": {"repositoryNameWithOwner": "owner/repo", "branchName": "feature"},
+                    "expectedHeadOid": PARENT_SHA,
+                    "message": {"headline": "Update docs", "body": "Details"},
+                    "fileChanges": {
+                        "additions": [
+                            {"path": "a.md", "contents": "IyBB"},
+                            {"path": "final.md", "contents": "RHJhZnQ="}
+                        ],
+                        "deletions": [{"path": "old.md"}, {"path": "draft.md"}]
+                    }
+                }}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"data": {"createCommitOnBranch": {"commit": {"oid": "7638417db6d59f3c431d3e1f261cc637155684cd"}}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let sha = client
+            .commit_builder("owner", "repo", "feature")
+            .message("Update docs\n\nDetails")
+            .add_file("a.md", "# A")
+            .delete_file("old.md")
+            .rename_file("draft.md", "final.md")
+            .commit_on_branch()
+            .await
+            .unwrap();
+        assert_eq!(sha, "7638417db6d59f3c431d3e1f261cc637155684cd");
+        mutation.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_commit_on_branch_rejects_modes_and_stale_head() {
+        let mut server = mockito::Server::new_async().await;
+        let _stale = server
+            .mock("POST", "/graphql")
+            .with_status(200)
+            .with_body(
+                r#"{"data": {"createCommitOnBranch": null}, "errors": [{"type": "STALE_DATA", "path": ["createCommitOnBranch"], "message": "Expected branch to point to \"abc\" but it did not. Pull and try again."}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let error = client
+            .commit_builder("owner", "repo", "feature")
+            .message("chmod")
+            .expected_head(PARENT_SHA)
+            .add_executable("run.sh", "#!/bin/sh")
+            .commit_on_branch()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+
+        let error = client
+            .commit_builder("owner", "repo", "feature")
+            .message("Update")
+            .expected_head("abc")
+            .add_file("a.md", "# A")
+            .commit_on_branch()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::GraphQLError { .. }));
+    }
+}
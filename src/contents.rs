@@ -0,0 +1,445 @@
+use crate::blob::decode_content;
+use crate::client::{encode_path, encode_query, GitHubClient};
+use crate::errors::GitHubError;
+use crate::models::{ContentType, ContentUpdate, Contents, FileCommit};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use reqwest::header::ACCEPT;
+use reqwest::Method;
+use serde::Serialize;
+use tracing::{debug, info};
+
+/// ファイルの中身をそのまま返させるメディアタイプ（100MB まで取得できる）
+const RAW_MEDIA_TYPE: &str = "application/vnd.github.raw";
+
+/// Contents API のパス（`git_ref` を省略するとデフォルトブランチ）
+///
+/// `.`・`..` を含むパスはエンドポイントの外を指しうるため `InvalidRequestError` にする。
+fn contents_path(
+    owner: &str,
+    repo: &str,
+    path: &str,
+    git_ref: Option<&str>,
+) -> Result<String, GitHubError> {
+    if path
+        .split('/')
+        .any(|component| component == "." || component == "..")
+    {
+        return Err(GitHubError::InvalidRequestError(format!(
+            "Invalid contents path {:?}: cannot contain '.' or '..' components",
+            path
+        )));
+    }
+    let mut url = format!(
+        "/repos/{}/{}/contents/{}",
+        owner,
+        repo,
+        encode_path(path.trim_matches('/'))
+    );
+    if let Some(git_ref) = git_ref {
+        url.push_str(&format!("?ref={}", encode_query(git_ref)));
+    }
+    Ok(url)
+}
+
+/// `PUT /contents/{path}` のリクエストボディ
+#[derive(Serialize)]
+struct PutFileBody<'a> {
+    content: String,
+    #[serde(flatten)]
+    update: &'a ContentUpdate,
+}
+
+impl GitHubClient {
+    /// パスのファイルまたはディレクトリの一覧を取得する
+    ///
+    /// `git_ref` にはブランチ名・タグ名・コミット SHA を指定できる。
+    pub async fn get_contents(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<Contents, GitHubError> {
+        let path = contents_path(owner, repo, path, git_ref)?;
+        Ok(self.get(&path).await?.json().await?)
+    }
+
+    /// ファイルの中身を raw メディアタイプで取得する
+    ///
+    /// base64 を経由しないため、1MB を超えるファイルも取得できる。
+    pub async fn get_raw_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<Bytes, GitHubError> {
+        let path = contents_path(owner, repo, path, git_ref)?;
+        let request = self
+            .request(Method::GET, &path)
+            .header(ACCEPT, RAW_MEDIA_TYPE);
+        let response = self.send(Method::GET, &path, request, true).await?;
+        Ok(response.bytes().await?)
+    }
+
+    /// `git_ref` 時点のファイルの中身を読み込む
+    ///
+    /// 1MB 以下のファイルは Contents API の内容をデコードし、それより大きい
+    /// ファイルは raw メディアタイプで取得し直す。ディレクトリなどファイル以外の
+    /// パスは `InvalidRequestError` になる。
+    pub async fn read_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: &str,
+        path: &str,
+    ) -> Result<Bytes, GitHubError> {
+        let item = match self.get_contents(owner, repo, path, Some(git_ref)).await? {
+            Contents::File(item) if item.content_type == ContentType::File => item,
+            _ => {
+                return Err(GitHubError::InvalidRequestError(format!(
+                    "{} is not a file at {}",
+                    path, git_ref
+                )))
+            }
+        };
+
+        match (item.content.as_deref(), item.encoding.as_deref()) {
+            (Some(content), encoding) if !content.is_empty() && encoding != Some("none") => {
+                Ok(decode_content(content, encoding)?.into())
+            }
+            _ => {
+                debug!(
+                    target: "github_client",
+                    %path,
+                    size = item.size,
+                    "Content not inlined; downloading raw file"
+                );
+                self.get_raw_file(owner, repo, path, Some(git_ref)).await
+            }
+        }
+    }
+
+    /// 1つのファイルを作成・更新し、そのコミットを作る
+    ///
+    /// 既存ファイルを更新する場合は `update.sha` に現在の BLOB SHA を指定する。
+    /// ブランチ上のファイルがその SHA と異なれば、上書きせずに 409 Conflict
+    /// （`GitHubError::is_conflict`）を返す。
+    pub async fn put_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        content: impl AsRef<[u8]>,
+        update: &ContentUpdate,
+    ) -> Result<FileCommit, GitHubError> {
+        let url = contents_path(owner, repo, path, None)?;
+        let body = PutFileBody {
+            content: STANDARD.encode(content),
+            update,
+        };
+        let response = self.send_content_update(Method::PUT, &url, &body).await?;
+        let result: FileCommit = response.json().await?;
+        info!(
+            target: "github_client",
+            %owner, %repo, %path,
+            commit = %result.commit.sha,
+            "File written"
+        );
+        Ok(result)
+    }
+
+    /// 1つのファイルを削除し、そのコミットを作る
+    ///
+    /// `update.sha` に削除するファイルの現在の BLOB SHA が必要。
+    pub async fn delete_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        update: &ContentUpdate,
+    ) -> Result<FileCommit, GitHubError> {
+        if update.sha.is_none() {
+            return Err(GitHubError::InvalidRequestError(format!(
+                "Deleting {} requires the current file SHA",
+                path
+            )));
+        }
+        let url = contents_path(owner, repo, path, None)?;
+        let response = self
+            .send_content_update(Method::DELETE, &url, update)
+            .await?;
+        let result: FileCommit = response.json().await?;
+        info!(
+            target: "github_client",
+            %owner, %repo, %path,
+            commit = %result.commit.sha,
+            "File deleted"
+        );
+        Ok(result)
+    }
+
+    /// 再送すると成功済みの変更が SHA の不一致（409）として返るため、再試行しない
+    async fn send_content_update<T: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &T,
+    ) -> Result<reqwest::Response, GitHubError> {
+        let request = self.request(method.clone(), path).json(body);
+        self.send(method, path, request, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GitUser;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    fn file_json(path: &str, content: &str, encoding: &str, size: u64) -> serde_json::Value {
+        json!({
+            "type": "file",
+            "name": path.rsplit('/').next().unwrap(),
+            "path": path,
+            "sha": "3d21ec53a331a6f037a91c368710b99387d012c1",
+            "size": size,
+            "url": format!("https://api.github.com/repos/owner/repo/contents/{}", path),
+            "download_url": format!("https://raw.githubusercontent.com/owner/repo/main/{}", path),
+            "content": content,
+            "encoding": encoding
+        })
+    }
+
+    fn client(server: &mockito::ServerGuard) -> GitHubClient {
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+        client
+    }
+
+    #[test]
+    fn test_contents_path() {
+        assert_eq!(
+            contents_path("owner", "repo", "/config/app #1.toml", Some("release/v1.0")).unwrap(),
+            "/repos/owner/repo/contents/config/app%20%231.toml?ref=release%2Fv1.0"
+        );
+        assert_eq!(
+            contents_path("owner", "repo", "", None).unwrap(),
+            "/repos/owner/repo/contents/"
+        );
+        for path in ["../../../user", "docs/../README.md", "./README.md"] {
+            assert!(matches!(
+                contents_path("owner", "repo", path, None),
+                Err(GitHubError::InvalidRequestError(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_contents_directory() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/repos/owner/repo/contents/config?ref=main")
+            .with_status(200)
+            .with_body(
+                json!([
+                    {
+                        "type": "file", "name": "app.toml", "path": "config/app.toml",
+                        "sha": "a1", "size": 12, "url": "https://api.github.com/a1"
+                    },
+                    {
+                        "type": "dir", "name": "env", "path": "config/env",
+                        "sha": "d1", "size": 0, "url": "https://api.github.com/d1"
+                    }
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let contents = client(&server)
+            .get_contents("owner", "repo", "config", Some("main"))
+            .await
+            .unwrap();
+        let Contents::Directory(items) = contents else {
+            panic!("expected a directory");
+        };
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].content_type, ContentType::Dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_file_decodes_content() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/repos/owner/repo/contents/config/app.toml?ref=main")
+            .with_status(200)
+            .with_body(
+                file_json("config/app.toml", "bmFtZSA9\nICJhcHAiCg==\n", "base64", 13).to_string(),
+            )
+            .create_async()
+            .await;
+
+        let content = client(&server)
+            .read_file("owner", "repo", "main", "config/app.toml")
+            .await
+            .unwrap();
+        assert_eq!(&content[..], b"name = \"app\"\n");
+    }
+
+    #[tokio::test]
+    async fn test_read_file_downloads_large_file_raw() {
+        let mut server = mockito::Server::new_async().await;
+        let _json = server
+            .mock("GET", "/repos/owner/repo/contents/data/big.bin?ref=v2")
+            .match_header("accept", "application/vnd.github.v3+json")
+            .with_status(200)
+            .with_body(file_json("data/big.bin", "", "none", 2_000_000).to_string())
+            .create_async()
+            .await;
+        let raw = server
+            .mock("GET", "/repos/owner/repo/contents/data/big.bin?ref=v2")
+            .match_header("accept", RAW_MEDIA_TYPE)
+            .match_header("authorization", Matcher::Regex("test_token".to_string()))
+            .with_status(200)
+            .with_body(vec![0u8, 159, 146, 150])
+            .create_async()
+            .await;
+
+        let content = client(&server)
+            .read_file("owner", "repo", "v2", "data/big.bin")
+            .await
+            .unwrap();
+        assert_eq!(&content[..], [0u8, 159, 146, 150]);
+        raw.assert_async().await;
+    }
+
+    fn file_commit_json(content: Option<serde_json::Value>) -> String {
+        json!({
+            "content": content,
+            "commit": {
+                "sha": "7638417db6d59f3c431d3e1f261cc637155684cd",
+                "url": "https://api.github.com/repos/owner/repo/git/commits/7638417d",
+                "message": "Update config",
+                "tree": {"sha": "691272480426f78a0138979dd3ce63b77f706feb", "url": "https://api.github.com/t"},
+                "parents": [{"sha": "1acc419d4d6a9ce985db7be48c6349a0475975b5", "url": "https://api.github.com/p"}]
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_put_file_sends_sha_and_branch() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/repos/owner/repo/contents/config/app.toml")
+            .match_body(Matcher::Json(json!({
+                "message": "Update config",
+                "content": "bmFtZSA9ICJhcHAiCg==",
+                "sha": "3d21ec53a331a6f037a91c368710b99387d012c1",
+                "branch": "release",
+                "committer": {"name": "Bot", "email": "bot@example.com"}
+            })))
+            .with_status(200)
+            .with_body(file_commit_json(Some(file_json(
+                "config/app.toml",
+                "",
+                "base64",
+                13,
+            ))))
+            .create_async()
+            .await;
+
+        let update = ContentUpdate::new("Update config")
+            .sha("3d21ec53a331a6f037a91c368710b99387d012c1")
+            .branch("release")
+            .committer(GitUser::new("Bot", "bot@example.com"));
+        let result = client(&server)
+            .put_file(
+                "owner",
+                "repo",
+                "config/app.toml",
+                "name = \"app\"\n",
+                &update,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result.commit.sha,
+            "7638417db6d59f3c431d3e1f261cc637155684cd"
+        );
+        assert_eq!(result.content.unwrap().path, "config/app.toml");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_put_file_conflict_is_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/repos/owner/repo/contents/config/app.toml")
+            .with_status(409)
+            .with_body(r#"{"message": "config/app.toml does not match 3d21ec53"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let update = ContentUpdate::new("Update config").sha("3d21ec53");
+        let error = client(&server)
+            .put_file("owner", "repo", "config/app.toml", b"x", &update)
+            .await
+            .unwrap_err();
+        assert!(error.is_conflict());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_file() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("DELETE", "/repos/owner/repo/contents/old.txt")
+            .match_body(Matcher::Json(json!({
+                "message": "Remove old.txt",
+                "sha": "a1",
+                "branch": "main"
+            })))
+            .with_status(200)
+            .with_body(file_commit_json(None))
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        let update = ContentUpdate::new("Remove old.txt").branch("main");
+        let error = client
+            .delete_file("owner", "repo", "old.txt", &update)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+
+        let result = client
+            .delete_file("owner", "repo", "old.txt", &update.sha("a1"))
+            .await
+            .unwrap();
+        assert!(result.content.is_none());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_file_rejects_directory() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/repos/owner/repo/contents/src?ref=main")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let error = client(&server)
+            .read_file("owner", "repo", "main", "src")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+    }
+}
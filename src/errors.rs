@@ -1,3 +1,8 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,25 +13,264 @@ pub enum GitHubError {
     #[error("JSON parsing error: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    #[error("Failed to parse response: {0}")]
+    ParseError(String),
+
     #[error("Authentication error: {0}")]
     AuthError(String),
 
-    #[error("Rate limit exceeded")]
-    RateLimitError,
+    #[error("Unauthorized: {message}")]
+    Unauthorized {
+        message: String,
+        documentation_url: Option<String>,
+    },
+
+    #[error("Forbidden: {message}")]
+    Forbidden {
+        message: String,
+        documentation_url: Option<String>,
+    },
+
+    #[error("Rate limit exceeded{}", reset.map(|r| format!(" (resets at {})", r)).unwrap_or_default())]
+    RateLimitError {
+        limit: Option<u64>,
+        reset: Option<DateTime<Utc>>,
+        message: String,
+    },
+
+    #[error("Secondary rate limit exceeded: {message}")]
+    SecondaryRateLimitError {
+        retry_after: Option<Duration>,
+        message: String,
+    },
 
     #[error("Resource not found: {0}")]
     NotFoundError(String),
 
+    #[error("Validation failed: {message}")]
+    ValidationError {
+        message: String,
+        errors: Vec<ValidationErrorDetail>,
+        documentation_url: Option<String>,
+    },
+
     #[error("Invalid request: {0}")]
     InvalidRequestError(String),
 
+    #[error("Invalid client configuration: {0}")]
+    ConfigError(String),
+
+    #[error("Blob of {size} bytes exceeds the {limit} byte limit")]
+    BlobTooLarge { size: u64, limit: u64 },
+
+    #[error("Commit signing failed: {0}")]
+    SigningError(String),
+
+    #[error("Patch does not apply: {}", .0.iter().map(|f| f.to_string()).collect::<Vec<_>>().join("; "))]
+    PatchFailed(Vec<crate::patch::PatchFailure>),
+
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
     #[error("GitHub API error: {status_code} - {message}")]
     ApiError { status_code: u16, message: String },
+
+    #[error("GraphQL error: {message}")]
+    GraphQLError {
+        message: String,
+        errors: Vec<GraphQLErrorDetail>,
+    },
+}
+
+/// GraphQL レスポンスの `errors` 配列の1要素
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GraphQLErrorDetail {
+    pub message: String,
+    /// `NOT_FOUND`・`FORBIDDEN`・`RATE_LIMITED` などのエラーの種類
+    #[serde(rename = "type", default)]
+    pub error_type: Option<String>,
+    /// 失敗したフィールドのパス（`["repository", "pullRequest"]` など）
+    #[serde(default)]
+    pub path: Vec<serde_json::Value>,
+}
+
+/// 422 レスポンスの `errors` 配列の1要素
+///
+/// 通常は `resource`・`field`・`code` を持つオブジェクトだが、文字列だけを返す
+/// エンドポイントもあり、その場合は `message` に入る。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "RawValidationErrorDetail")]
+pub struct ValidationErrorDetail {
+    pub resource: Option<String>,
+    pub field: Option<String>,
+    pub code: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawValidationErrorDetail {
+    Object {
+        resource: Option<String>,
+        field: Option<String>,
+        code: Option<String>,
+        message: Option<String>,
+    },
+    Message(String),
+}
+
+impl From<RawValidationErrorDetail> for ValidationErrorDetail {
+    fn from(raw: RawValidationErrorDetail) -> Self {
+        match raw {
+            RawValidationErrorDetail::Object {
+                resource,
+                field,
+                code,
+                message,
+            } => Self {
+                resource,
+                field,
+                code,
+                message,
+            },
+            RawValidationErrorDetail::Message(message) => Self {
+                resource: None,
+                field: None,
+                code: None,
+                message: Some(message),
+            },
+        }
+    }
+}
+
+/// REST API が返すエラーレスポンスの本文
+#[derive(Debug, Default, Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    documentation_url: Option<String>,
+    #[serde(default)]
+    errors: Vec<ValidationErrorDetail>,
+}
+
+impl GitHubError {
+    /// エラーに対応する HTTP ステータス（あれば）
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            GitHubError::HttpError(e) => e.status(),
+            GitHubError::Unauthorized { .. } => Some(StatusCode::UNAUTHORIZED),
+            GitHubError::Forbidden { .. } => Some(StatusCode::FORBIDDEN),
+            GitHubError::NotFoundError(_) => Some(StatusCode::NOT_FOUND),
+            GitHubError::ValidationError { .. } => Some(StatusCode::UNPROCESSABLE_ENTITY),
+            GitHubError::ApiError { status_code, .. } => StatusCode::from_u16(*status_code).ok(),
+            _ => None,
+        }
+    }
+
+    /// プライマリ・セカンダリのレート制限によるエラーかどうか
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(
+            self,
+            GitHubError::RateLimitError { .. } | GitHubError::SecondaryRateLimitError { .. }
+        )
+    }
+
+    /// 409 Conflict かどうか（Contents API で指定したファイルの SHA がブランチと異なるなど）
+    pub fn is_conflict(&self) -> bool {
+        self.status() == Some(StatusCode::CONFLICT)
+    }
+
+    /// fast-forward でないためにリファレンスの更新が拒否されたか（読み込み後にブランチが動いた）
+    pub fn is_non_fast_forward(&self) -> bool {
+        matches!(
+            self,
+            GitHubError::ValidationError { message, .. }
+                if message.to_lowercase().contains("not a fast forward")
+        )
+    }
+
+    /// 失敗レスポンスの本文を読み込んでエラーに変換する
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        match response.bytes().await {
+            Ok(body) => Self::from_parts(status, &headers, &body),
+            Err(e) => GitHubError::HttpError(e),
+        }
+    }
+
+    /// ステータス・ヘッダー・本文から対応するバリアントを作る
+    pub fn from_parts(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let parsed: ErrorBody = serde_json::from_slice(body).unwrap_or_default();
+        let message = parsed.message.unwrap_or_else(|| {
+            let text = String::from_utf8_lossy(body).trim().to_string();
+            if text.is_empty() {
+                status
+                    .canonical_reason()
+                    .unwrap_or("Unknown error")
+                    .to_string()
+            } else {
+                text
+            }
+        });
+        let documentation_url = parsed.documentation_url;
+
+        if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+            if header_u64(headers, "x-ratelimit-remaining") == Some(0) {
+                return GitHubError::RateLimitError {
+                    limit: header_u64(headers, "x-ratelimit-limit"),
+                    reset: header_u64(headers, "x-ratelimit-reset")
+                        .and_then(|secs| Utc.timestamp_opt(secs as i64, 0).single()),
+                    message,
+                };
+            }
+            let retry_after = header_u64(headers, "retry-after").map(Duration::from_secs);
+            if retry_after.is_some()
+                || status == StatusCode::TOO_MANY_REQUESTS
+                || message.to_lowercase().contains("secondary rate limit")
+            {
+                return GitHubError::SecondaryRateLimitError {
+                    retry_after,
+                    message,
+                };
+            }
+        }
+
+        match status {
+            StatusCode::UNAUTHORIZED => GitHubError::Unauthorized {
+                message,
+                documentation_url,
+            },
+            StatusCode::FORBIDDEN => GitHubError::Forbidden {
+                message,
+                documentation_url,
+            },
+            StatusCode::NOT_FOUND => GitHubError::NotFoundError(message),
+            StatusCode::UNPROCESSABLE_ENTITY => GitHubError::ValidationError {
+                message,
+                errors: parsed.errors,
+                documentation_url,
+            },
+            _ => GitHubError::ApiError {
+                status_code: status.as_u16(),
+                message,
+            },
+        }
+    }
+}
+
+pub(crate) fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reqwest::header::HeaderValue;
 
     #[test]
     fn test_error_display() {
@@ -42,4 +286,113 @@ mod tests {
             "GitHub API error: 422 - Validation failed"
         );
     }
+
+    #[test]
+    fn test_from_parts_validation_error() {
+        let body = br#"{
+            "message": "Validation Failed",
+            "errors": [
+                {"resource": "PullRequest", "code": "custom", "message": "A pull request already exists"},
+                "head sha can't be blank"
+            ],
+            "documentation_url": "https://docs.github.com/rest/pulls/pulls#create-a-pull-request"
+        }"#;
+        let error =
+            GitHubError::from_parts(StatusCode::UNPROCESSABLE_ENTITY, &HeaderMap::new(), body);
+
+        match error {
+            GitHubError::ValidationError {
+                message,
+                errors,
+                documentation_url,
+            } => {
+                assert_eq!(message, "Validation Failed");
+                assert_eq!(errors.len(), 2);
+                assert_eq!(errors[0].resource.as_deref(), Some("PullRequest"));
+                assert_eq!(errors[0].code.as_deref(), Some("custom"));
+                assert_eq!(
+                    errors[1].message.as_deref(),
+                    Some("head sha can't be blank")
+                );
+                assert!(documentation_url.is_some());
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_parts_rate_limits() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("5000"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1700000000"));
+        let error = GitHubError::from_parts(
+            StatusCode::FORBIDDEN,
+            &headers,
+            br#"{"message": "API rate limit exceeded"}"#,
+        );
+        match error {
+            GitHubError::RateLimitError { limit, reset, .. } => {
+                assert_eq!(limit, Some(5000));
+                assert_eq!(reset.unwrap().timestamp(), 1_700_000_000);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("60"));
+        let error = GitHubError::from_parts(
+            StatusCode::FORBIDDEN,
+            &headers,
+            br#"{"message": "You have exceeded a secondary rate limit."}"#,
+        );
+        match error {
+            GitHubError::SecondaryRateLimitError { retry_after, .. } => {
+                assert_eq!(retry_after, Some(Duration::from_secs(60)));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_parts_status_mapping() {
+        let headers = HeaderMap::new();
+        let error = GitHubError::from_parts(
+            StatusCode::NOT_FOUND,
+            &headers,
+            br#"{"message": "Not Found"}"#,
+        );
+        assert!(matches!(error, GitHubError::NotFoundError(ref m) if m == "Not Found"));
+        assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+
+        let error = GitHubError::from_parts(
+            StatusCode::UNAUTHORIZED,
+            &headers,
+            br#"{"message": "Bad credentials"}"#,
+        );
+        assert!(matches!(error, GitHubError::Unauthorized { .. }));
+
+        let error = GitHubError::from_parts(StatusCode::FORBIDDEN, &headers, b"");
+        assert!(
+            matches!(error, GitHubError::Forbidden { ref message, .. } if message == "Forbidden")
+        );
+
+        let error = GitHubError::from_parts(StatusCode::BAD_GATEWAY, &headers, b"bad gateway");
+        assert_eq!(error.status(), Some(StatusCode::BAD_GATEWAY));
+
+        let error = GitHubError::from_parts(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            &headers,
+            br#"{"message": "Update is not a fast forward"}"#,
+        );
+        assert!(error.is_non_fast_forward());
+
+        let error = GitHubError::from_parts(
+            StatusCode::CONFLICT,
+            &headers,
+            br#"{"message": "config/app.toml does not match 3d21ec53"}"#,
+        );
+        assert!(error.is_conflict());
+        assert!(!error.is_non_fast_forward());
+    }
 }
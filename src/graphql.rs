@@ -0,0 +1,404 @@
+use crate::client::GitHubClient;
+use crate::errors::{GitHubError, GraphQLErrorDetail};
+use futures::stream::{self, Stream, TryStreamExt};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+/// GraphQL のレート制限超過を表すエラー種別
+const RATE_LIMITED: &str = "RATE_LIMITED";
+
+#[derive(Serialize)]
+struct GraphQLRequest<'a, V: ?Sized> {
+    query: &'a str,
+    variables: &'a V,
+}
+
+/// GraphQL のレスポンス
+///
+/// `data` と `errors` が両方返る（部分的な成功）こともある。
+#[derive(Debug, Clone, Deserialize)]
+pub struct GraphQLResponse<T> {
+    #[serde(default = "Option::default")]
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<GraphQLErrorDetail>,
+}
+
+/// `pageInfo` フィールド
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    #[serde(default)]
+    pub end_cursor: Option<String>,
+}
+
+/// `nodes` と `pageInfo` を持つコネクション
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Connection<T> {
+    #[serde(default = "Vec::new")]
+    pub nodes: Vec<T>,
+    pub page_info: PageInfo,
+    #[serde(default)]
+    pub total_count: Option<u64>,
+}
+
+/// クエリに `rateLimit { cost limit remaining used resetAt }` を含めた場合の結果
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLRateLimit {
+    pub cost: u64,
+    pub limit: u64,
+    pub remaining: u64,
+    #[serde(default)]
+    pub used: Option<u64>,
+    pub reset_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// コメントを除いた先頭が `mutation` で始まるか
+fn is_mutation(query: &str) -> bool {
+    query
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .is_some_and(|line| line.starts_with("mutation"))
+}
+
+impl GitHubClient {
+    /// GraphQL エンドポイントの URL
+    ///
+    /// GitHub Enterprise Server（`.../api/v3`）の場合は `.../api/graphql` になる。
+    pub fn graphql_url(&self) -> String {
+        if let Some(url) = &self.graphql_url {
+            return url.clone();
+        }
+        match self.base_url.strip_suffix("/api/v3") {
+            Some(origin) => format!("{}/api/graphql", origin),
+            None => format!("{}/graphql", self.base_url),
+        }
+    }
+
+    /// クエリを送信し、`errors` を含むレスポンスをそのまま返す
+    pub async fn graphql_response<T, V>(
+        &self,
+        query: &str,
+        variables: &V,
+    ) -> Result<GraphQLResponse<T>, GitHubError>
+    where
+        T: DeserializeOwned,
+        V: Serialize + ?Sized,
+    {
+        let request = self
+            .request_url(Method::POST, &self.graphql_url())
+            .json(&GraphQLRequest { query, variables });
+        // クエリは何度送っても結果が変わらないが、ミューテーションは再送しない
+        let response = self
+            .send(Method::POST, "/graphql", request, !is_mutation(query))
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    /// クエリを送信し、`data` を型付きで返す
+    ///
+    /// `errors` が含まれる場合は `GraphQLError`（種別が `RATE_LIMITED` なら `RateLimitError`）を返す。
+    pub async fn graphql<T, V>(&self, query: &str, variables: &V) -> Result<T, GitHubError>
+    where
+        T: DeserializeOwned,
+        V: Serialize + ?Sized,
+    {
+        // エラー時は `data` の一部が null になり型に合わないため、先にエラーを確認する
+        let response = self.graphql_response::<Value, _>(query, variables).await?;
+        if !response.errors.is_empty() {
+            return Err(self.graphql_error(response.errors));
+        }
+        match response.data {
+            Some(data) => Ok(serde_json::from_value(data)?),
+            None => Err(GitHubError::ParseError(
+                "GraphQL response has no data".to_string(),
+            )),
+        }
+    }
+
+    /// カーソルでページングするクエリの全ノードを順に取得する `Stream`
+    ///
+    /// クエリは `$cursor: String` を受け取り、コネクションの `after: $cursor` に渡すこと。
+    /// `connection` はレスポンスの `data` から対象のコネクションを取り出す。
+    ///
+    /// ```no_run
+    /// # use futures::TryStreamExt;
+    /// # use github::graphql::Connection;
+    /// # async fn run(client: &github::client::GitHubClient) -> Result<(), github::errors::GitHubError> {
+    /// #[derive(serde::Deserialize)]
+    /// struct Data { repository: Repo }
+    /// #[derive(serde::Deserialize)]
+    /// #[serde(rename_all = "camelCase")]
+    /// struct Repo { pull_requests: Connection<Pr> }
+    /// #[derive(serde::Deserialize)]
+    /// struct Pr { number: u64 }
+    ///
+    /// let query = "query($owner: String!, $name: String!, $cursor: String) {
+    ///   repository(owner: $owner, name: $name) {
+    ///     pullRequests(first: 100, after: $cursor) { nodes { number } pageInfo { hasNextPage endCursor } }
+    ///   }
+    /// }";
+    /// let variables = serde_json::json!({"owner": "octocat", "name": "hello-world"});
+    /// let prs: Vec<Pr> = client
+    ///     .graphql_paginate(query, variables, |data: Data| data.repository.pull_requests)
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn graphql_paginate<'a, D, T, F>(
+        &'a self,
+        query: &'a str,
+        variables: Value,
+        connection: F,
+    ) -> impl Stream<Item = Result<T, GitHubError>> + 'a
+    where
+        D: DeserializeOwned + 'a,
+        T: 'a,
+        F: Fn(D) -> Connection<T> + 'a,
+    {
+        stream::try_unfold(
+            (Some(None::<String>), variables, connection),
+            move |(cursor, mut variables, connection)| async move {
+                let Some(cursor) = cursor else {
+                    return Ok::<_, GitHubError>(None);
+                };
+                let Some(object) = variables.as_object_mut() else {
+                    return Err(GitHubError::InvalidRequestError(
+                        "GraphQL variables must be a JSON object".to_string(),
+                    ));
+                };
+                object.insert("cursor".to_string(), cursor.into());
+                debug!(target: "github_client", cursor = ?object["cursor"], "Fetching GraphQL page");
+
+                let page = connection(self.graphql::<D, _>(query, &variables).await?);
+                let next = match page.page_info {
+                    PageInfo {
+                        has_next_page: true,
+                        end_cursor: Some(end_cursor),
+                    } => Some(Some(end_cursor)),
+                    _ => None,
+                };
+                Ok(Some((page.nodes, (next, variables, connection))))
+            },
+        )
+        .map_ok(|nodes| stream::iter(nodes.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    fn graphql_error(&self, errors: Vec<GraphQLErrorDetail>) -> GitHubError {
+        let message = errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        if errors
+            .iter()
+            .any(|e| e.error_type.as_deref() == Some(RATE_LIMITED))
+        {
+            let limit = self.graphql_rate_limit();
+            return GitHubError::RateLimitError {
+                limit: limit.as_ref().map(|l| l.limit),
+                reset: limit.map(|l| l.reset),
+                message,
+            };
+        }
+        GitHubError::GraphQLError { message, errors }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    struct Viewer {
+        viewer: Login,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Login {
+        login: String,
+    }
+
+    fn client(url: String) -> GitHubClient {
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = url;
+        client
+    }
+
+    #[test]
+    fn test_graphql_url() {
+        let client = GitHubClient::builder()
+            .token("t")
+            .enterprise("github.example.com")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.graphql_url(),
+            "https://github.example.com/api/graphql"
+        );
+        assert_eq!(
+            GitHubClient::new("t".to_string()).graphql_url(),
+            "https://api.github.com/graphql"
+        );
+    }
+
+    #[test]
+    fn test_is_mutation() {
+        assert!(is_mutation("\n  # comment\n  mutation($input: X!) { a }"));
+        assert!(!is_mutation("query { viewer { login } }"));
+        assert!(!is_mutation("{ viewer { login } }"));
+    }
+
+    #[tokio::test]
+    async fn test_graphql_query_with_variables() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/graphql")
+            .match_body(Matcher::Json(json!({
+                "query": "query($n: Int!) { viewer { login } }",
+                "variables": {"n": 1}
+            })))
+            .with_status(200)
+            .with_header("x-ratelimit-limit", "5000")
+            .with_header("x-ratelimit-remaining", "4990")
+            .with_header("x-ratelimit-used", "10")
+            .with_header("x-ratelimit-reset", "1700000000")
+            .with_header("x-ratelimit-resource", "graphql")
+            .with_body(r#"{"data": {"viewer": {"login": "octocat"}}}"#)
+            .create_async()
+            .await;
+
+        let client = client(server.url());
+        let data: Viewer = client
+            .graphql("query($n: Int!) { viewer { login } }", &json!({"n": 1}))
+            .await
+            .unwrap();
+        assert_eq!(data.viewer.login, "octocat");
+        assert_eq!(client.graphql_rate_limit().unwrap().used, Some(10));
+        assert!(client.rate_limit().is_none());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_graphql_errors_are_mapped() {
+        let mut server = mockito::Server::new_async().await;
+        let _not_found = server
+            .mock("POST", "/graphql")
+            .match_body(Matcher::Regex("missing".to_string()))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": {"repository": null},
+                    "errors": [{
+                        "type": "NOT_FOUND",
+                        "path": ["repository"],
+                        "message": "Could not resolve to a Repository with the name 'octocat/missing'."
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _limited = server
+            .mock("POST", "/graphql")
+            .match_body(Matcher::Regex("viewer".to_string()))
+            .with_status(200)
+            .with_body(
+                r#"{"errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = client(server.url());
+        let error = client
+            .graphql::<Value, _>(
+                r#"{ repository(owner: "octocat", name: "missing") { id } }"#,
+                &json!({}),
+            )
+            .await
+            .unwrap_err();
+        match error {
+            GitHubError::GraphQLError { errors, .. } => {
+                assert_eq!(errors[0].error_type.as_deref(), Some("NOT_FOUND"));
+                assert_eq!(errors[0].path, vec![json!("repository")]);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let error = client
+            .graphql::<Viewer, _>("{ viewer { login } }", &json!({}))
+            .await
+            .unwrap_err();
+        assert!(error.is_rate_limited());
+    }
+
+    #[tokio::test]
+    async fn test_graphql_paginate() {
+        #[derive(Deserialize)]
+        struct Data {
+            repository: Repo,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Repo {
+            pull_requests: Connection<Pr>,
+        }
+        #[derive(Deserialize)]
+        struct Pr {
+            number: u64,
+        }
+
+        let query = "query($cursor: String) { repository { pullRequests(first: 2, after: $cursor) { nodes { number } pageInfo { hasNextPage endCursor } } } }";
+        let mut server = mockito::Server::new_async().await;
+        let _first = server
+            .mock("POST", "/graphql")
+            .match_body(Matcher::PartialJson(json!({"variables": {"cursor": null}})))
+            .with_status(200)
+            .with_body(
+                json!({"data": {"repository": {"pullRequests": {
+                    "nodes": [{"number": 1}, {"number": 2}],
+                    "pageInfo": {"hasNextPage": true, "endCursor": "Y3Vyc29yOjI="}
+                }}}})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _second = server
+            .mock("POST", "/graphql")
+            .match_body(Matcher::PartialJson(
+                json!({"variables": {"cursor": "Y3Vyc29yOjI=", "owner": "octocat"}}),
+            ))
+            .with_status(200)
+            .with_body(
+                json!({"data": {"repository": {"pullRequests": {
+                    "nodes": [{"number": 3}],
+                    "pageInfo": {"hasNextPage": false, "endCursor": "Y3Vyc29yOjM="}
+                }}}})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = client(server.url());
+        let prs: Vec<Pr> = client
+            .graphql_paginate(query, json!({"owner": "octocat"}), |data: Data| {
+                data.repository.pull_requests
+            })
+            .try_collect()
+            .await
+            .unwrap();
+        let numbers: Vec<u64> = prs.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+    }
+}
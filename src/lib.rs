@@ -1,6 +1,20 @@
 pub mod auth;
+pub mod blob;
+pub mod cache;
 pub mod client;
+pub mod commit;
+pub mod contents;
 pub mod errors;
+pub mod graphql;
+pub mod models;
+pub mod pagination;
+pub mod patch;
+pub mod rate_limit;
+pub mod redact;
+pub mod refs;
+pub mod retry;
+pub mod signing;
+pub mod tree;
 
 pub fn init_tracing() {
     tracing_subscriber::fmt()
@@ -9,6 +23,7 @@ pub fn init_tracing() {
         .with_thread_ids(true)
         .with_file(true)
         .with_line_number(true)
+        .with_writer(redact::RedactingMakeWriter::new(std::io::stdout))
         .init();
 }
 
@@ -0,0 +1,662 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// GitHub ユーザー（またはOrganization / Bot）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub login: String,
+    pub id: u64,
+    pub node_id: String,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    #[serde(rename = "type", default)]
+    pub user_type: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+/// リポジトリ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Repository {
+    pub id: u64,
+    pub node_id: String,
+    pub name: String,
+    pub full_name: String,
+    pub owner: User,
+    pub private: bool,
+    pub html_url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub fork: bool,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub clone_url: Option<String>,
+}
+
+/// Git リファレンス（`refs/heads/main` など）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitRef {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    #[serde(default)]
+    pub node_id: Option<String>,
+    pub url: String,
+    pub object: GitObject,
+}
+
+/// リファレンスが指すオブジェクト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitObject {
+    pub sha: String,
+    #[serde(rename = "type")]
+    pub object_type: String,
+    pub url: String,
+}
+
+/// コミットの作者・コミッター情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitUser {
+    pub name: String,
+    pub email: String,
+    /// 省略するとコミット作成時の時刻になる
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_git_date"
+    )]
+    pub date: Option<DateTime<Utc>>,
+}
+
+impl GitUser {
+    pub fn new(name: impl Into<String>, email: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            email: email.into(),
+            date: None,
+        }
+    }
+
+    /// 日時を指定する（git オブジェクトは秒単位のため端数は切り捨てられる）
+    pub fn with_date(mut self, date: DateTime<Utc>) -> Self {
+        self.date = Some(date);
+        self
+    }
+}
+
+/// API が受け付ける `YYYY-MM-DDTHH:MM:SSZ` 形式で送信する
+fn serialize_git_date<S: Serializer>(
+    date: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match date {
+        Some(date) => serializer.collect_str(&date.format("%Y-%m-%dT%H:%M:%SZ")),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// コミットが指すツリー、または親コミットへの参照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShaRef {
+    pub sha: String,
+    pub url: String,
+    #[serde(default)]
+    pub html_url: Option<String>,
+}
+
+/// コミット署名の検証結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verification {
+    pub verified: bool,
+    pub reason: String,
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub payload: Option<String>,
+}
+
+/// Git コミットオブジェクト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Commit {
+    pub sha: String,
+    #[serde(default)]
+    pub node_id: Option<String>,
+    pub url: String,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    pub message: String,
+    pub tree: ShaRef,
+    #[serde(default)]
+    pub parents: Vec<ShaRef>,
+    #[serde(default)]
+    pub author: Option<GitUser>,
+    #[serde(default)]
+    pub committer: Option<GitUser>,
+    #[serde(default)]
+    pub verification: Option<Verification>,
+}
+
+/// ツリーエントリのファイルモード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FileMode {
+    /// 通常ファイル
+    #[serde(rename = "100644")]
+    File,
+    /// 実行可能ファイル
+    #[serde(rename = "100755")]
+    Executable,
+    /// サブディレクトリ
+    #[serde(rename = "040000")]
+    Tree,
+    /// シンボリックリンク（BLOBの内容がリンク先パス）
+    #[serde(rename = "120000")]
+    Symlink,
+    /// サブモジュール（gitlink）
+    #[serde(rename = "160000")]
+    Submodule,
+}
+
+impl FileMode {
+    /// このモードのエントリが指すオブジェクトの種類
+    pub fn object_type(self) -> ObjectType {
+        match self {
+            FileMode::File | FileMode::Executable | FileMode::Symlink => ObjectType::Blob,
+            FileMode::Tree => ObjectType::Tree,
+            FileMode::Submodule => ObjectType::Commit,
+        }
+    }
+}
+
+/// ツリーエントリが指すオブジェクトの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectType {
+    Blob,
+    Tree,
+    Commit,
+}
+
+/// ツリーのエントリ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeEntry {
+    pub path: String,
+    pub mode: FileMode,
+    #[serde(rename = "type")]
+    pub entry_type: ObjectType,
+    pub sha: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Git ツリーオブジェクト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tree {
+    pub sha: String,
+    pub url: String,
+    #[serde(default)]
+    pub tree: Vec<TreeEntry>,
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// ツリー作成時に送信するエントリ
+///
+/// `sha` が `None` の場合は `null` として送信され、そのパスは削除される。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewTreeEntry {
+    pub path: String,
+    pub mode: FileMode,
+    #[serde(rename = "type")]
+    pub entry_type: ObjectType,
+    pub sha: Option<String>,
+}
+
+impl NewTreeEntry {
+    /// 任意のモードでオブジェクトを配置するエントリ
+    pub fn new(path: impl Into<String>, mode: FileMode, sha: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            mode,
+            entry_type: mode.object_type(),
+            sha: Some(sha.into()),
+        }
+    }
+
+    /// 通常ファイル（`100644`）のエントリ
+    pub fn file(path: impl Into<String>, blob_sha: impl Into<String>) -> Self {
+        Self::new(path, FileMode::File, blob_sha)
+    }
+
+    /// 実行可能ファイル（`100755`）のエントリ
+    pub fn executable(path: impl Into<String>, blob_sha: impl Into<String>) -> Self {
+        Self::new(path, FileMode::Executable, blob_sha)
+    }
+
+    /// シンボリックリンク（`120000`）のエントリ
+    ///
+    /// `blob_sha` はリンク先パスを内容とするBLOBを指す。
+    pub fn symlink(path: impl Into<String>, blob_sha: impl Into<String>) -> Self {
+        Self::new(path, FileMode::Symlink, blob_sha)
+    }
+
+    /// サブモジュール（`160000`）を指定コミットに固定するエントリ
+    pub fn submodule(path: impl Into<String>, commit_sha: impl Into<String>) -> Self {
+        Self::new(path, FileMode::Submodule, commit_sha)
+    }
+
+    /// パスを削除するエントリ
+    pub fn delete(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            mode: FileMode::File,
+            entry_type: ObjectType::Blob,
+            sha: None,
+        }
+    }
+}
+
+/// コミット作成時に送信する内容
+///
+/// `parents` が空の場合は親を持たないルートコミットになり、2つ以上の場合はマージコミットになる。
+///
+/// ```
+/// use github::models::{GitUser, NewCommit};
+///
+/// let commit = NewCommit::new("Merge feature", "691272480426f78a0138979dd3ce63b77f706feb")
+///     .parent("6dcb09b5b57875f334f61aebed695e2e4193db5e")
+///     .parent("553c2077f0edc3d5dc5d17262f6aa498e69d6f8e")
+///     .author(GitUser::new("Mona Octocat", "octocat@github.com"));
+/// assert_eq!(commit.parents.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewCommit {
+    pub message: String,
+    pub tree: String,
+    pub parents: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<GitUser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committer: Option<GitUser>,
+    /// コミットオブジェクトに対する ASCII armor 形式の署名（GPG / SSH）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl NewCommit {
+    /// 親を持たないコミット（親は `parent` / `parents` で追加する）
+    pub fn new(message: impl Into<String>, tree_sha: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            tree: tree_sha.into(),
+            parents: Vec::new(),
+            author: None,
+            committer: None,
+            signature: None,
+        }
+    }
+
+    pub fn parent(mut self, sha: impl Into<String>) -> Self {
+        self.parents.push(sha.into());
+        self
+    }
+
+    pub fn parents<I, S>(mut self, shas: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.parents.extend(shas.into_iter().map(Into::into));
+        self
+    }
+
+    /// 作者（省略時は認証ユーザー）
+    pub fn author(mut self, author: GitUser) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// コミッター（省略時は作者と同じ）
+    pub fn committer(mut self, committer: GitUser) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    pub fn signature(mut self, signature: impl Into<String>) -> Self {
+        self.signature = Some(signature.into());
+        self
+    }
+}
+
+/// Git BLOB オブジェクト
+///
+/// 作成時のレスポンスには `sha` と `url` のみが含まれる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Blob {
+    pub sha: String,
+    pub url: String,
+    #[serde(default)]
+    pub node_id: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+/// Contents API が返すエントリの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentType {
+    File,
+    Dir,
+    Symlink,
+    Submodule,
+}
+
+/// Contents API のファイル・ディレクトリ
+///
+/// `content` はファイルを単体で取得した場合のみ含まれる。1MB を超えるファイルでは
+/// 空文字列になり、`encoding` は `"none"` になる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentItem {
+    #[serde(rename = "type")]
+    pub content_type: ContentType,
+    pub name: String,
+    pub path: String,
+    pub sha: String,
+    pub size: u64,
+    pub url: String,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub git_url: Option<String>,
+    #[serde(default)]
+    pub download_url: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// シンボリックリンクのリンク先
+    #[serde(default)]
+    pub target: Option<String>,
+    /// サブモジュールのリポジトリ URL
+    #[serde(default)]
+    pub submodule_git_url: Option<String>,
+}
+
+/// Contents API のレスポンス（パスがディレクトリなら一覧、それ以外は単体）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Contents {
+    Directory(Vec<ContentItem>),
+    File(Box<ContentItem>),
+}
+
+/// Contents API でファイルを作成・更新・削除するときのコミット情報
+///
+/// ```
+/// use github::models::{ContentUpdate, GitUser};
+///
+/// let update = ContentUpdate::new("Bump version")
+///     .sha("3d21ec53a331a6f037a91c368710b99387d012c1")
+///     .branch("main")
+///     .committer(GitUser::new("Release Bot", "release-bot@example.com"));
+/// assert_eq!(update.branch.as_deref(), Some("main"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentUpdate {
+    pub message: String,
+    /// 更新・削除するファイルの現在の BLOB SHA（一致しなければ 409 Conflict になる）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+    /// 省略するとデフォルトブランチ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committer: Option<GitUser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<GitUser>,
+}
+
+impl ContentUpdate {
+    /// 新規作成用（既存ファイルを更新する場合は `sha` を指定する）
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            sha: None,
+            branch: None,
+            committer: None,
+            author: None,
+        }
+    }
+
+    pub fn sha(mut self, sha: impl Into<String>) -> Self {
+        self.sha = Some(sha.into());
+        self
+    }
+
+    pub fn branch(mut self, branch: impl Into<String>) -> Self {
+        self.branch = Some(branch.into());
+        self
+    }
+
+    /// 省略すると認証ユーザー（GitHub App ではアプリのボット）になる
+    pub fn committer(mut self, committer: GitUser) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    /// 省略するとコミッターと同じになる
+    pub fn author(mut self, author: GitUser) -> Self {
+        self.author = Some(author);
+        self
+    }
+}
+
+/// Contents API による作成・更新・削除の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileCommit {
+    /// 書き込んだファイル（削除した場合は `None`）
+    #[serde(default)]
+    pub content: Option<ContentItem>,
+    pub commit: Commit,
+}
+
+/// プルリクエストのヘッド・ベース
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestRef {
+    pub label: String,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub sha: String,
+    #[serde(default)]
+    pub user: Option<User>,
+    #[serde(default)]
+    pub repo: Option<Repository>,
+}
+
+/// プルリクエスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub id: u64,
+    pub node_id: String,
+    pub number: u64,
+    pub state: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub url: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub user: Option<User>,
+    pub head: PullRequestRef,
+    pub base: PullRequestRef,
+    #[serde(default)]
+    pub draft: Option<bool>,
+    #[serde(default)]
+    pub merged: Option<bool>,
+    #[serde(default)]
+    pub mergeable: Option<bool>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_pull_request() {
+        let value = json!({
+            "id": 1,
+            "node_id": "PR_kwDOA",
+            "number": 1347,
+            "state": "open",
+            "title": "Amazing new feature",
+            "body": null,
+            "url": "https://api.github.com/repos/octocat/Hello-World/pulls/1347",
+            "html_url": "https://github.com/octocat/Hello-World/pull/1347",
+            "head": {
+                "label": "octocat:new-topic",
+                "ref": "new-topic",
+                "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e"
+            },
+            "base": {
+                "label": "octocat:master",
+                "ref": "master",
+                "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e"
+            },
+            "created_at": "2011-01-26T19:01:12Z"
+        });
+
+        let pr: PullRequest = serde_json::from_value(value).unwrap();
+        assert_eq!(pr.number, 1347);
+        assert_eq!(pr.head.ref_name, "new-topic");
+        assert_eq!(pr.base.ref_name, "master");
+        assert!(pr.body.is_none());
+        assert_eq!(pr.created_at.unwrap().timestamp(), 1_296_068_472);
+    }
+
+    #[test]
+    fn test_serialize_new_tree_entry() {
+        let value = serde_json::to_value(NewTreeEntry::delete("old.txt")).unwrap();
+        assert_eq!(
+            value,
+            json!({"path": "old.txt", "mode": "100644", "type": "blob", "sha": null})
+        );
+    }
+
+    #[test]
+    fn test_serialize_special_tree_entries() {
+        let entries = vec![
+            NewTreeEntry::executable("bin/run.sh", "aaa"),
+            NewTreeEntry::symlink("current", "bbb"),
+            NewTreeEntry::submodule("vendor/lib", "ccc"),
+        ];
+        let value = serde_json::to_value(entries).unwrap();
+        assert_eq!(
+            value,
+            json!([
+                {"path": "bin/run.sh", "mode": "100755", "type": "blob", "sha": "aaa"},
+                {"path": "current", "mode": "120000", "type": "blob", "sha": "bbb"},
+                {"path": "vendor/lib", "mode": "160000", "type": "commit", "sha": "ccc"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_serialize_new_commit() {
+        let date = DateTime::parse_from_rfc3339("2024-05-01T09:30:15.250+09:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let root = NewCommit::new("Initial commit", "t1");
+        assert_eq!(
+            serde_json::to_value(&root).unwrap(),
+            json!({"message": "Initial commit", "tree": "t1", "parents": []})
+        );
+
+        let merge = NewCommit::new("Merge", "t2")
+            .parents(["p1", "p2"])
+            .author(GitUser::new("Mona", "mona@example.com").with_date(date))
+            .committer(GitUser::new("Bot", "bot@example.com"))
+            .signature("-----BEGIN PGP SIGNATURE-----\n...");
+        assert_eq!(
+            serde_json::to_value(&merge).unwrap(),
+            json!({
+                "message": "Merge",
+                "tree": "t2",
+                "parents": ["p1", "p2"],
+                "author": {"name": "Mona", "email": "mona@example.com", "date": "2024-05-01T00:30:15Z"},
+                "committer": {"name": "Bot", "email": "bot@example.com"},
+                "signature": "-----BEGIN PGP SIGNATURE-----\n..."
+            })
+        );
+    }
+
+    #[test]
+    fn test_deserialize_tree() {
+        let value = json!({
+            "sha": "9fb037999f264ba9a7fc6274d15fa3ae2ab98312",
+            "url": "https://api.github.com/repos/octocat/Hello-World/trees/9fb037999f264ba9a7fc6274d15fa3ae2ab98312",
+            "tree": [
+                {"path": "file.rb", "mode": "100644", "type": "blob", "size": 30, "sha": "44b4fc6d56897b048c772eb4087f854f46256132"},
+                {"path": "subdir", "mode": "040000", "type": "tree", "sha": "f484d249c660418515fb01c2b9662073663c242e"},
+                {"path": "exec_file", "mode": "100755", "type": "blob", "size": 75, "sha": "45b983be36b73c0788dc9cbcb76cbb80fc7bb057"},
+                {"path": "lib", "mode": "160000", "type": "commit", "sha": "7638417db6d59f3c431d3e1f261cc637155684cd"}
+            ],
+            "truncated": false
+        });
+
+        let tree: Tree = serde_json::from_value(value).unwrap();
+        assert_eq!(tree.tree[0].mode, FileMode::File);
+        assert_eq!(tree.tree[1].entry_type, ObjectType::Tree);
+        assert_eq!(tree.tree[2].mode, FileMode::Executable);
+        assert_eq!(tree.tree[3].mode.object_type(), ObjectType::Commit);
+    }
+
+    #[test]
+    fn test_deserialize_commit() {
+        let value = json!({
+            "sha": "7638417db6d59f3c431d3e1f261cc637155684cd",
+            "node_id": "MDY6Q29tbWl0NzYzODQxN2RiNmQ1OWYzYzQzMWQzZTFmMjYxY2M2MzcxNTU2ODRjZA==",
+            "url": "https://api.github.com/repos/octocat/Hello-World/git/commits/7638417db6d59f3c431d3e1f261cc637155684cd",
+            "message": "added readme, because im a good github citizen",
+            "author": {
+                "date": "2014-11-07T22:01:45Z",
+                "name": "Monalisa Octocat",
+                "email": "octocat@github.com"
+            },
+            "tree": {
+                "url": "https://api.github.com/repos/octocat/Hello-World/git/trees/691272480426f78a0138979dd3ce63b77f706feb",
+                "sha": "691272480426f78a0138979dd3ce63b77f706feb"
+            },
+            "parents": [{
+                "url": "https://api.github.com/repos/octocat/Hello-World/git/commits/1acc419d4d6a9ce985db7be48c6349a0475975b5",
+                "sha": "1acc419d4d6a9ce985db7be48c6349a0475975b5"
+            }],
+            "verification": {
+                "verified": false,
+                "reason": "unsigned",
+                "signature": null,
+                "payload": null
+            }
+        });
+
+        let commit: Commit = serde_json::from_value(value).unwrap();
+        assert_eq!(commit.tree.sha, "691272480426f78a0138979dd3ce63b77f706feb");
+        assert_eq!(commit.parents.len(), 1);
+        assert_eq!(commit.author.unwrap().name, "Monalisa Octocat");
+        assert!(!commit.verification.unwrap().verified);
+    }
+}
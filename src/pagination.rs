@@ -0,0 +1,169 @@
+use crate::client::GitHubClient;
+use crate::errors::GitHubError;
+use futures::stream::{self, Stream, TryStreamExt};
+use reqwest::header::LINK;
+use serde::de::DeserializeOwned;
+use tracing::debug;
+
+/// 1ページ分の結果と次ページのパス
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<String>,
+}
+
+/// `Link` ヘッダーから指定した `rel` の URL を取り出す
+///
+/// 例: `<https://api.github.com/user/repos?page=2>; rel="next", <...>; rel="last"`
+pub fn parse_link_header(value: &str, rel: &str) -> Option<String> {
+    value.split(',').find_map(|part| {
+        let mut segments = part.split(';');
+        let url = segments
+            .next()?
+            .trim()
+            .strip_prefix('<')?
+            .strip_suffix('>')?;
+        segments
+            .any(|param| {
+                let param = param.trim();
+                param == format!("rel=\"{}\"", rel) || param == format!("rel={}", rel)
+            })
+            .then(|| url.to_string())
+    })
+}
+
+/// パスに `per_page` クエリを付与する
+fn with_per_page(path: &str, per_page: Option<u32>) -> String {
+    match per_page {
+        Some(n) => {
+            let sep = if path.contains('?') { '&' } else { '?' };
+            format!("{}{}per_page={}", path, sep, n)
+        }
+        None => path.to_string(),
+    }
+}
+
+impl GitHubClient {
+    /// 1ページ分を取得し、`Link: rel="next"` があれば次ページのパスを返す
+    pub async fn get_page<T: DeserializeOwned>(&self, path: &str) -> Result<Page<T>, GitHubError> {
+        let response = self.get(path).await?;
+        let next = response
+            .headers()
+            .get(LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| parse_link_header(v, "next"))
+            .map(|url| self.relative_path(&url))
+            .transpose()?;
+        let items = response.json().await?;
+        Ok(Page { items, next })
+    }
+
+    /// 一覧系エンドポイントの全ページを順に取得する `Stream`
+    ///
+    /// 次のページは前のページを読み終えた時点で遅延取得される。
+    pub fn paginate<'a, T>(
+        &'a self,
+        path: &str,
+        per_page: Option<u32>,
+    ) -> impl Stream<Item = Result<T, GitHubError>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        let first = with_per_page(path, per_page);
+        stream::try_unfold(Some(first), move |next| async move {
+            let Some(path) = next else {
+                return Ok::<_, GitHubError>(None);
+            };
+            debug!(target: "github_client", %path, "Fetching page");
+            let page = self.get_page::<T>(&path).await?;
+            Ok(Some((page.items, page.next)))
+        })
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// 全ページを取得して `Vec` にまとめる
+    pub async fn collect_all<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        per_page: Option<u32>,
+    ) -> Result<Vec<T>, GitHubError> {
+        self.paginate(path, per_page).try_collect().await
+    }
+
+    /// `Link` ヘッダーの絶対 URL をベース URL からの相対パスに変換する
+    fn relative_path(&self, url: &str) -> Result<String, GitHubError> {
+        url.strip_prefix(self.base_url.as_str())
+            .map(String::from)
+            .ok_or_else(|| {
+                GitHubError::ParseError(format!(
+                    "Pagination link {} is outside of {}",
+                    url, self.base_url
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        id: u32,
+    }
+
+    #[test]
+    fn test_parse_link_header() {
+        let header = r#"<https://api.github.com/user/repos?page=2>; rel="next", <https://api.github.com/user/repos?page=5>; rel="last""#;
+        assert_eq!(
+            parse_link_header(header, "next").as_deref(),
+            Some("https://api.github.com/user/repos?page=2")
+        );
+        assert_eq!(
+            parse_link_header(header, "last").as_deref(),
+            Some("https://api.github.com/user/repos?page=5")
+        );
+        assert_eq!(parse_link_header(header, "prev"), None);
+    }
+
+    #[test]
+    fn test_with_per_page() {
+        assert_eq!(
+            with_per_page("/user/repos", Some(50)),
+            "/user/repos?per_page=50"
+        );
+        assert_eq!(
+            with_per_page("/user/repos?type=owner", Some(50)),
+            "/user/repos?type=owner&per_page=50"
+        );
+        assert_eq!(with_per_page("/user/repos", None), "/user/repos");
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_follows_next_links() {
+        let mut server = mockito::Server::new_async().await;
+        let next = format!("{}/items?per_page=2&page=2", server.url());
+        let _first = server
+            .mock("GET", "/items?per_page=2")
+            .with_status(200)
+            .with_header("link", &format!(r#"<{}>; rel="next""#, next))
+            .with_body(r#"[{"id": 1}, {"id": 2}]"#)
+            .create_async()
+            .await;
+        let _second = server
+            .mock("GET", "/items?per_page=2&page=2")
+            .with_status(200)
+            .with_body(r#"[{"id": 3}]"#)
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let items: Vec<Item> = client.collect_all("/items", Some(2)).await.unwrap();
+        let ids: Vec<u32> = items.iter().map(|i| i.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,1284 @@
+//! unified diff をブランチへ適用する
+//!
+//! `git diff` / `git format-patch` / `diff -u` の出力を解析し、ブランチの先頭の
+//! ファイルに hunk を当てて、結果を git data API で1つのコミットにする。
+//! リポジトリをクローンする必要はない。
+//!
+//! ```no_run
+//! # async fn run(client: &github::client::GitHubClient, diff: &str) -> Result<(), github::errors::GitHubError> {
+//! use github::errors::GitHubError;
+//! use github::patch::Patch;
+//!
+//! let patch = Patch::parse(diff)?;
+//! match client
+//!     .commit_builder("owner", "repo", "feature")
+//!     .message("Apply generated changes")
+//!     .apply_patch(&patch)
+//!     .await
+//! {
+//!     Ok(result) => println!("{}", result.sha),
+//!     Err(GitHubError::PatchFailed(failures)) => {
+//!         for failure in failures {
+//!             eprintln!("{}", failure);
+//!         }
+//!     }
+//!     Err(e) => return Err(e),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::GitHubClient;
+use crate::commit::{CommitBuilder, FileChange};
+use crate::errors::GitHubError;
+use crate::models::{FileMode, ObjectType, TreeEntry};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+use tracing::{info, warn};
+
+/// hunk の前後から無視してよいコンテキスト行数の既定値（GNU patch と同じ）
+pub const DEFAULT_MAX_FUZZ: usize = 2;
+
+/// 複数ファイル分の unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    pub files: Vec<FilePatch>,
+}
+
+/// 1ファイル分の差分
+///
+/// 追加されるファイルは `old_path`、削除されるファイルは `new_path` が `None` になる。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub old_mode: Option<FileMode>,
+    pub new_mode: Option<FileMode>,
+    /// `copy from` / `copy to`（元のファイルは残る）
+    pub copy: bool,
+    /// バイナリの差分（適用できない）
+    pub binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+/// `@@ -old_start,old_lines +new_start,new_lines @@` で始まる変更のまとまり
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<HunkLine>,
+    /// 変更前の最終行に改行がない（`\ No newline at end of file`）
+    pub old_missing_newline: bool,
+    /// 変更後の最終行に改行がない
+    pub new_missing_newline: bool,
+}
+
+/// hunk の1行（行末の改行は含まない）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkLine {
+    Context(String),
+    Delete(String),
+    Insert(String),
+}
+
+/// 適用できた hunk
+///
+/// `offset` と `fuzz` がどちらも0でなければ、差分の作成後にファイルが変更されている。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedHunk {
+    pub path: String,
+    /// ファイル内での hunk の番号（1始まり）
+    pub hunk: usize,
+    /// 適用した位置（変更前のファイルでの行番号、1始まり）
+    pub line: usize,
+    /// hunk ヘッダーの行番号からのずれ
+    pub offset: isize,
+    /// 一致させるために無視した前後のコンテキスト行数
+    pub fuzz: usize,
+}
+
+/// 適用できなかった hunk・ファイル
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchFailure {
+    pub path: String,
+    /// ファイル単位の失敗（存在しない、バイナリなど）では `None`
+    pub hunk: Option<usize>,
+    pub reason: String,
+}
+
+impl fmt::Display for PatchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hunk {
+            Some(hunk) => write!(f, "{}: hunk #{}: {}", self.path, hunk, self.reason),
+            None => write!(f, "{}: {}", self.path, self.reason),
+        }
+    }
+}
+
+/// パッチを適用したコミット
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchResult {
+    pub sha: String,
+    pub hunks: Vec<AppliedHunk>,
+}
+
+impl PatchResult {
+    /// 位置のずれ・fuzz があった hunk（内容を確認したほうがよいもの）
+    pub fn inexact_hunks(&self) -> impl Iterator<Item = &AppliedHunk> {
+        self.hunks.iter().filter(|h| h.offset != 0 || h.fuzz > 0)
+    }
+}
+
+impl Patch {
+    /// unified diff を解析する
+    ///
+    /// `diff --git` の拡張ヘッダー（追加・削除・リネーム・コピー・モード変更）に対応する。
+    /// `git format-patch` のメールヘッダーやコミットメッセージなど、差分以外の行は読み飛ばす。
+    /// パスの `a/`・`b/` 接頭辞は取り除かれる。
+    pub fn parse(text: &str) -> Result<Self, GitHubError> {
+        // CRLF のファイルの差分では `\r` も行の内容なので、`\n` だけで分ける
+        let lines: Vec<&str> = text
+            .strip_suffix('\n')
+            .unwrap_or(text)
+            .split('\n')
+            .collect();
+        let mut files = Vec::new();
+        let mut current: Option<FilePatch> = None;
+        // `diff --git` の直後で、まだ hunk が始まっていない
+        let mut in_git_header = false;
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            if let Some(rest) = line.strip_prefix("diff --git ") {
+                files.extend(current.take());
+                let (old, new) = parse_git_header_paths(rest).ok_or_else(|| {
+                    invalid_patch(i, format!("cannot parse file names in {:?}", line))
+                })?;
+                current = Some(FilePatch {
+                    old_path: Some(old),
+                    new_path: Some(new),
+                    ..FilePatch::default()
+                });
+                in_git_header = true;
+            } else if line.starts_with("--- ")
+                && lines.get(i + 1).is_some_and(|l| l.starts_with("+++ "))
+            {
+                let old = parse_header_path(&line[4..], "a/");
+                let new = parse_header_path(&lines[i + 1][4..], "b/");
+                match current.as_mut().filter(|_| in_git_header) {
+                    Some(file) => {
+                        file.old_path = old;
+                        file.new_path = new;
+                    }
+                    None => {
+                        files.extend(current.take());
+                        current = Some(FilePatch {
+                            old_path: old,
+                            new_path: new,
+                            ..FilePatch::default()
+                        });
+                    }
+                }
+                in_git_header = false;
+                i += 1;
+            } else if line.starts_with("@@ ") {
+                let file = current
+                    .as_mut()
+                    .ok_or_else(|| invalid_patch(i, "hunk without a file header".to_string()))?;
+                let (hunk, next) = parse_hunk(&lines, i)?;
+                file.hunks.push(hunk);
+                in_git_header = false;
+                i = next;
+                continue;
+            } else if let Some(file) = current.as_mut().filter(|_| in_git_header) {
+                parse_extended_header(file, line).map_err(|reason| invalid_patch(i, reason))?;
+            }
+            i += 1;
+        }
+        files.extend(current);
+
+        if files.is_empty() {
+            return Err(GitHubError::InvalidRequestError(
+                "Patch does not contain any file changes".to_string(),
+            ));
+        }
+        Ok(Self { files })
+    }
+}
+
+impl FilePatch {
+    /// 差分を識別するためのパス（変更後、削除の場合は変更前）
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+fn invalid_patch(index: usize, reason: String) -> GitHubError {
+    GitHubError::InvalidRequestError(format!("Invalid patch at line {}: {}", index + 1, reason))
+}
+
+fn parse_extended_header(file: &mut FilePatch, line: &str) -> Result<(), String> {
+    if let Some(mode) = line.strip_prefix("new file mode ") {
+        file.old_path = None;
+        file.new_mode = Some(parse_mode(mode)?);
+    } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+        file.new_path = None;
+        file.old_mode = Some(parse_mode(mode)?);
+    } else if let Some(mode) = line.strip_prefix("old mode ") {
+        file.old_mode = Some(parse_mode(mode)?);
+    } else if let Some(mode) = line.strip_prefix("new mode ") {
+        file.new_mode = Some(parse_mode(mode)?);
+    } else if let Some(path) = line.strip_prefix("rename from ") {
+        file.old_path = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("rename to ") {
+        file.new_path = Some(unquote(path));
+    } else if let Some(path) = line.strip_prefix("copy from ") {
+        file.old_path = Some(unquote(path));
+        file.copy = true;
+    } else if let Some(path) = line.strip_prefix("copy to ") {
+        file.new_path = Some(unquote(path));
+        file.copy = true;
+    } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+        file.binary = true;
+    }
+    Ok(())
+}
+
+fn parse_mode(mode: &str) -> Result<FileMode, String> {
+    match mode.trim() {
+        "100644" | "100664" => Ok(FileMode::File),
+        "100755" => Ok(FileMode::Executable),
+        "120000" => Ok(FileMode::Symlink),
+        "160000" => Ok(FileMode::Submodule),
+        other => Err(format!("unsupported file mode {}", other)),
+    }
+}
+
+/// `diff --git a/x b/y` のパス部分を解析する
+fn parse_git_header_paths(rest: &str) -> Option<(String, String)> {
+    let (old, new) = if rest.starts_with('"') {
+        let (old, remaining) = split_quoted(rest)?;
+        (old, unquote(remaining.trim_start()))
+    } else if let Some(index) = rest.find(" \"") {
+        (rest[..index].to_string(), unquote(&rest[index + 1..]))
+    } else {
+        // 名前に空白を含む場合も、変更前後で同じ名前なら中央で分けられる
+        let half = rest.len() / 2;
+        match (rest.get(..half), rest.get(half + 1..)) {
+            (Some(a), Some(b))
+                if rest.len() % 2 == 1
+                    && rest.as_bytes()[half] == b' '
+                    && a.strip_prefix("a/").is_some()
+                    && a.strip_prefix("a/") == b.strip_prefix("b/") =>
+            {
+                (a.to_string(), b.to_string())
+            }
+            _ => {
+                let (a, b) = rest.rsplit_once(" b/")?;
+                (a.to_string(), format!("b/{}", b))
+            }
+        }
+    };
+    Some((strip_prefix(old, "a/"), strip_prefix(new, "b/")))
+}
+
+/// `--- a/path\t日時` のパス部分（`/dev/null` は `None`）
+fn parse_header_path(value: &str, prefix: &str) -> Option<String> {
+    let path = if value.starts_with('"') {
+        unquote(value)
+    } else {
+        value.split('\t').next().unwrap_or(value).to_string()
+    };
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(strip_prefix(path, prefix))
+}
+
+fn strip_prefix(path: String, prefix: &str) -> String {
+    match path.strip_prefix(prefix) {
+        Some(stripped) => stripped.to_string(),
+        None => path,
+    }
+}
+
+/// git が引用符で囲んだパス（`"a/\343\201\202.txt"`）を元に戻す
+fn unquote(value: &str) -> String {
+    match split_quoted(value) {
+        Some((path, _)) => path,
+        None => value.to_string(),
+    }
+}
+
+/// 先頭の引用符付き文字列と、その後ろの残りを返す
+fn split_quoted(value: &str) -> Option<(String, &str)> {
+    let body = value.strip_prefix('"')?;
+    let mut bytes = Vec::new();
+    let mut chars = body.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => {
+                return Some((
+                    String::from_utf8_lossy(&bytes).into_owned(),
+                    &body[index + 1..],
+                ))
+            }
+            '\\' => {
+                let (_, escaped) = chars.next()?;
+                match escaped {
+                    'n' => bytes.push(b'\n'),
+                    't' => bytes.push(b'\t'),
+                    'a' => bytes.push(0x07),
+                    'b' => bytes.push(0x08),
+                    'f' => bytes.push(0x0c),
+                    'r' => bytes.push(b'\r'),
+                    'v' => bytes.push(0x0b),
+                    '0'..='3' => {
+                        let mut value = escaped.to_digit(8)?;
+                        for _ in 0..2 {
+                            value = value * 8 + chars.next()?.1.to_digit(8)?;
+                        }
+                        bytes.push(value as u8);
+                    }
+                    other => {
+                        let mut buf = [0; 4];
+                        bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+                    }
+                }
+            }
+            other => {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+    None
+}
+
+/// `lines[start]` の hunk ヘッダーから hunk を読み、次の行の位置とともに返す
+fn parse_hunk(lines: &[&str], start: usize) -> Result<(Hunk, usize), GitHubError> {
+    let header = lines[start];
+    let ranges = header
+        .strip_prefix("@@ -")
+        .and_then(|rest| rest.split_once(" @@"))
+        .map(|(ranges, _)| ranges)
+        .and_then(|ranges| ranges.split_once(" +"))
+        .ok_or_else(|| invalid_patch(start, format!("invalid hunk header {:?}", header)))?;
+    let parse_range = |range: &str| -> Result<(usize, usize), GitHubError> {
+        let (line, count) = range.split_once(',').unwrap_or((range, "1"));
+        match (line.parse(), count.parse()) {
+            (Ok(line), Ok(count)) => Ok((line, count)),
+            _ => Err(invalid_patch(
+                start,
+                format!("invalid hunk header {:?}", header),
+            )),
+        }
+    };
+    let (old_start, old_lines) = parse_range(ranges.0)?;
+    let (new_start, new_lines) = parse_range(ranges.1)?;
+
+    let mut hunk = Hunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+        old_missing_newline: false,
+        new_missing_newline: false,
+    };
+    let (mut old_remaining, mut new_remaining) = (old_lines, new_lines);
+    let mut i = start + 1;
+    loop {
+        match lines.get(i) {
+            Some(line) if line.starts_with('\\') => hunk.mark_missing_newline(),
+            _ if old_remaining == 0 && new_remaining == 0 => break,
+            Some(line) => {
+                let text = line.get(1..).unwrap_or_default().to_string();
+                let (hunk_line, old, new) = match line.as_bytes().first() {
+                    // 末尾の空白を削るエディタのため、空行はコンテキストとして扱う
+                    Some(b' ') | None => (HunkLine::Context(text), 1, 1),
+                    Some(b'-') => (HunkLine::Delete(text), 1, 0),
+                    Some(b'+') => (HunkLine::Insert(text), 0, 1),
+                    _ => return Err(invalid_patch(i, format!("unexpected line {:?}", line))),
+                };
+                match (
+                    old_remaining.checked_sub(old),
+                    new_remaining.checked_sub(new),
+                ) {
+                    (Some(o), Some(n)) => (old_remaining, new_remaining) = (o, n),
+                    _ => {
+                        return Err(invalid_patch(
+                            i,
+                            "hunk is longer than its header".to_string(),
+                        ))
+                    }
+                }
+                hunk.lines.push(hunk_line);
+            }
+            None => {
+                return Err(invalid_patch(
+                    i.min(lines.len()),
+                    "hunk is truncated".to_string(),
+                ))
+            }
+        }
+        i += 1;
+    }
+    Ok((hunk, i))
+}
+
+impl Hunk {
+    /// `\ No newline at end of file` を直前の行に反映する
+    fn mark_missing_newline(&mut self) {
+        match self.lines.last() {
+            Some(HunkLine::Context(_)) => {
+                self.old_missing_newline = true;
+                self.new_missing_newline = true;
+            }
+            Some(HunkLine::Delete(_)) => self.old_missing_newline = true,
+            Some(HunkLine::Insert(_)) => self.new_missing_newline = true,
+            None => {}
+        }
+    }
+
+    fn old_side(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Delete(text) => Some(text.as_str()),
+                HunkLine::Insert(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_side(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Insert(text) => Some(text.as_str()),
+                HunkLine::Delete(_) => None,
+            })
+            .collect()
+    }
+
+    /// 先頭・末尾のコンテキスト行数
+    fn context(&self) -> (usize, usize) {
+        let is_context = |line: &&HunkLine| matches!(line, HunkLine::Context(_));
+        let leading = self.lines.iter().take_while(is_context).count();
+        if leading == self.lines.len() {
+            return (leading, 0);
+        }
+        (
+            leading,
+            self.lines.iter().rev().take_while(is_context).count(),
+        )
+    }
+
+    /// ヘッダーが示す、変更前のファイルでの開始位置（0始まり）
+    fn expected_index(&self) -> usize {
+        if self.old_lines == 0 {
+            self.old_start
+        } else {
+            self.old_start.saturating_sub(1)
+        }
+    }
+}
+
+/// hunk が一致した位置
+struct HunkMatch {
+    start: usize,
+    /// 無視したコンテキストを含む開始行（1始まり）
+    line: usize,
+    old_len: usize,
+    /// 変更後の行のうち、当てる範囲
+    new_range: Range<usize>,
+    offset: isize,
+    fuzz: usize,
+}
+
+/// `lines[from..]` の中で hunk が一致する位置を、期待する位置に近い順に探す
+///
+/// 一致しなければ、前後のコンテキストを `max_fuzz` 行まで無視して探し直す。
+fn locate(
+    lines: &[&str],
+    from: usize,
+    hunk: &Hunk,
+    old_side: &[&str],
+    new_len: usize,
+    offset: isize,
+    max_fuzz: usize,
+) -> Option<HunkMatch> {
+    let (leading, trailing) = hunk.context();
+    let expected = hunk.expected_index() as isize;
+    let mut tried = Vec::new();
+    for fuzz in 0..=max_fuzz {
+        let (top, bottom) = (fuzz.min(leading), fuzz.min(trailing));
+        if tried.contains(&(top, bottom)) {
+            continue;
+        }
+        tried.push((top, bottom));
+
+        let old = &old_side[top..old_side.len() - bottom];
+        if old.len() > lines.len().saturating_sub(from) {
+            continue;
+        }
+        // ファイルの先頭の hunk は前のコンテキストが短いため、先頭にしか当てない
+        let at_start = top == 0 && leading < trailing && hunk.expected_index() == 0;
+        let last = lines.len() - old.len();
+        let base = (expected + offset + top as isize).clamp(from as isize, last as isize) as usize;
+        let candidates = (0..=last - from).flat_map(|distance| {
+            let after = base.checked_add(distance).filter(|p| *p <= last);
+            let before = base
+                .checked_sub(distance)
+                .filter(|p| distance > 0 && *p >= from);
+            after.into_iter().chain(before)
+        });
+        for start in candidates {
+            if at_start && start != 0 {
+                continue;
+            }
+            if lines[start..start + old.len()] == *old {
+                return Some(HunkMatch {
+                    start,
+                    line: start - top + 1,
+                    old_len: old.len(),
+                    new_range: top..new_len - bottom,
+                    offset: start as isize - top as isize - hunk.expected_index() as isize,
+                    fuzz,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// テキストに hunk を順に当てる
+///
+/// 適用できなかった hunk は飛ばし、結果とともに hunk ごとの成否を返す。
+pub fn apply_hunks(
+    path: &str,
+    text: &str,
+    hunks: &[Hunk],
+    max_fuzz: usize,
+) -> (String, Vec<Result<AppliedHunk, PatchFailure>>) {
+    let mut newline_at_end = text.is_empty() || text.ends_with('\n');
+    let lines: Vec<&str> = if text.is_empty() {
+        Vec::new()
+    } else {
+        text.strip_suffix('\n')
+            .unwrap_or(text)
+            .split('\n')
+            .collect()
+    };
+
+    let mut output: Vec<&str> = Vec::new();
+    let mut reports = Vec::new();
+    let mut cursor = 0;
+    let mut offset = 0;
+    for (index, hunk) in hunks.iter().enumerate() {
+        let (old_side, new_side) = (hunk.old_side(), hunk.new_side());
+        match locate(
+            &lines,
+            cursor,
+            hunk,
+            &old_side,
+            new_side.len(),
+            offset,
+            max_fuzz,
+        ) {
+            Some(found) => {
+                output.extend_from_slice(&lines[cursor..found.start]);
+                output.extend_from_slice(&new_side[found.new_range]);
+                cursor = found.start + found.old_len;
+                offset = found.offset;
+                if cursor == lines.len() {
+                    if hunk.new_missing_newline {
+                        newline_at_end = false;
+                    } else if hunk.old_missing_newline {
+                        newline_at_end = true;
+                    }
+                }
+                reports.push(Ok(AppliedHunk {
+                    path: path.to_string(),
+                    hunk: index + 1,
+                    line: found.line,
+                    offset: found.offset,
+                    fuzz: found.fuzz,
+                }));
+            }
+            None => reports.push(Err(PatchFailure {
+                path: path.to_string(),
+                hunk: Some(index + 1),
+                reason: format!(
+                    "context not found near line {} (fuzz up to {})",
+                    hunk.old_start, max_fuzz
+                ),
+            })),
+        }
+    }
+    output.extend_from_slice(&lines[cursor..]);
+
+    let mut result = output.join("\n");
+    if newline_at_end && !output.is_empty() {
+        result.push('\n');
+    }
+    (result, reports)
+}
+
+/// パッチ適用中のファイル
+#[derive(Debug, Clone)]
+enum WorkFile {
+    /// ブランチ先頭の内容のまま（`origin` はブランチ先頭でのパス）
+    Head {
+        origin: String,
+        sha: String,
+        mode: FileMode,
+    },
+    /// 内容を変更したファイル
+    Patched { content: Vec<u8>, mode: FileMode },
+}
+
+impl WorkFile {
+    fn mode(&self) -> FileMode {
+        match self {
+            WorkFile::Head { mode, .. } | WorkFile::Patched { mode, .. } => *mode,
+        }
+    }
+}
+
+impl<'a> CommitBuilder<'a> {
+    /// hunk の前後から無視してよいコンテキスト行数（`apply_patch()` のみ、既定は2）
+    pub fn max_fuzz(mut self, fuzz: usize) -> Self {
+        self.max_fuzz = fuzz;
+        self
+    }
+
+    /// パッチをブランチの先頭に当て、結果をコミットしてブランチを更新する
+    ///
+    /// パッチが触れるパスの親ディレクトリだけを1階層ずつ一覧し、変更されるファイルの
+    /// BLOB だけを取得するため、リポジトリ全体をダウンロードしない。
+    /// 1つでも適用できない hunk・ファイルがあればコミットせず、すべての失敗を
+    /// `GitHubError::PatchFailed` で返す。ブランチが先に更新されていた場合は、
+    /// 古い内容に当てた結果で上書きしないよう `max_retries` に関係なく再試行しない。
+    pub async fn apply_patch(mut self, patch: &Patch) -> Result<PatchResult, GitHubError> {
+        if self.message.is_none() {
+            return Err(GitHubError::InvalidRequestError(
+                "Commit message is required".to_string(),
+            ));
+        }
+        let client = self.client;
+        let (owner, repo) = (self.owner.clone(), self.repo.clone());
+        let head = self.head_sha().await?;
+        let tree_sha = client.get_latest_tree_sha(&owner, &repo, &head).await?;
+        let paths = patch
+            .files
+            .iter()
+            .flat_map(|file| [file.old_path.as_deref(), file.new_path.as_deref()])
+            .flatten();
+        let head_files = head_files(client, &owner, &repo, &tree_sha, paths).await?;
+        info!(
+            target: "github_client",
+            %owner,
+            %repo,
+            branch = %self.branch,
+            %head,
+            files = patch.files.len(),
+            "Applying patch"
+        );
+
+        // 変更したパスの状態（`None` は削除）と、最初に変更した順序
+        let mut work: HashMap<String, Option<WorkFile>> = HashMap::new();
+        let mut touched: Vec<String> = Vec::new();
+        let mut applied = Vec::new();
+        let mut failures = Vec::new();
+        for file_patch in &patch.files {
+            let path = file_patch.path().to_string();
+            let fail = |reason: &str| PatchFailure {
+                path: path.clone(),
+                hunk: None,
+                reason: reason.to_string(),
+            };
+            if file_patch.binary {
+                failures.push(fail("binary patches are not supported"));
+                continue;
+            }
+            let lookup = |path: &str| match work.get(path) {
+                Some(file) => file.clone(),
+                None => head_files.get(path).cloned(),
+            };
+
+            let source = match &file_patch.old_path {
+                Some(old_path) => match lookup(old_path) {
+                    Some(file) => Some(file),
+                    None => {
+                        failures.push(fail(&if *old_path == path {
+                            "file does not exist".to_string()
+                        } else {
+                            format!("source {} does not exist", old_path)
+                        }));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            if let Some(new_path) = &file_patch.new_path {
+                let replaces_itself = file_patch.old_path.as_ref() == Some(new_path);
+                if !replaces_itself && lookup(new_path).is_some() {
+                    failures.push(fail("file already exists"));
+                    continue;
+                }
+            }
+            let mode = file_patch
+                .new_mode
+                .or(source.as_ref().map(WorkFile::mode))
+                .unwrap_or(FileMode::File);
+            if mode == FileMode::Submodule
+                || source.as_ref().map(WorkFile::mode) == Some(FileMode::Submodule)
+            {
+                failures.push(fail("submodule changes are not supported"));
+                continue;
+            }
+
+            // 内容が変わらない（リネームのみ・削除で内容の確認が不要）なら BLOB を取得しない
+            let keeps_content = file_patch.hunks.is_empty()
+                && (file_patch.new_path.is_none()
+                    || source.as_ref().is_some_and(|s| s.mode() == mode));
+            let result = match (&source, keeps_content) {
+                (Some(source), true) if !file_patch.copy => Some(source.clone()),
+                (source, _) => {
+                    let original = match source {
+                        Some(WorkFile::Patched { content, .. }) => content.clone(),
+                        Some(WorkFile::Head { sha, .. }) => {
+                            client.get_blob_content(&owner, &repo, sha).await?.to_vec()
+                        }
+                        None => Vec::new(),
+                    };
+                    let content = if file_patch.hunks.is_empty() {
+                        original
+                    } else {
+                        let Ok(text) = String::from_utf8(original) else {
+                            failures.push(fail("file is not UTF-8 text"));
+                            continue;
+                        };
+                        let (patched, reports) =
+                            apply_hunks(&path, &text, &file_patch.hunks, self.max_fuzz);
+                        let mut ok = true;
+                        for report in reports {
+                            match report {
+                                Ok(hunk) => applied.push(hunk),
+                                Err(failure) => {
+                                    ok = false;
+                                    failures.push(failure);
+                                }
+                            }
+                        }
+                        if !ok {
+                            continue;
+                        }
+                        patched.into_bytes()
+                    };
+                    if file_patch.new_path.is_none() && !content.is_empty() {
+                        failures.push(fail("deleted file does not match the patch"));
+                        continue;
+                    }
+                    Some(WorkFile::Patched { content, mode })
+                }
+            };
+
+            if let Some(old_path) = &file_patch.old_path {
+                if !file_patch.copy && file_patch.new_path.as_ref() != Some(old_path) {
+                    work.insert(old_path.clone(), None);
+                    touched.push(old_path.clone());
+                }
+            }
+            if let Some(new_path) = &file_patch.new_path {
+                work.insert(new_path.clone(), result);
+                touched.push(new_path.clone());
+            }
+        }
+
+        if !failures.is_empty() {
+            warn!(
+                target: "github_client",
+                failures = failures.len(),
+                "Patch does not apply"
+            );
+            return Err(GitHubError::PatchFailed(failures));
+        }
+
+        let mut seen = HashSet::new();
+        touched.retain(|path| seen.insert(path.clone()));
+        // 内容を変えずに移動したファイルは `Rename` にし、移動元の削除もそれに任せる
+        let mut renamed_from = HashSet::new();
+        for path in &touched {
+            if let Some(Some(WorkFile::Head { origin, .. })) = work.get(path) {
+                if origin != path {
+                    renamed_from.insert(origin.clone());
+                    self = self.change(FileChange::Rename {
+                        from: origin.clone(),
+                        to: path.clone(),
+                    });
+                }
+            }
+        }
+        for path in touched {
+            match work.remove(&path).flatten() {
+                Some(WorkFile::Patched { content, mode }) => {
+                    self = self.change(FileChange::Write {
+                        path,
+                        content,
+                        mode,
+                    });
+                }
+                None if head_files.contains_key(&path) && !renamed_from.contains(&path) => {
+                    self = self.change(FileChange::Delete { path });
+                }
+                _ => {}
+            }
+        }
+
+        // パッチを当てた先頭から動いていれば、ブランチの更新は fast-forward にならず失敗する
+        self.expected_head = Some(head);
+        self.max_retries = 0;
+        let sha = self.commit().await?;
+        Ok(PatchResult {
+            sha,
+            hunks: applied,
+        })
+    }
+}
+
+/// `paths` の先頭の状態を、親ディレクトリごとに1回だけツリーを一覧して調べる
+///
+/// 存在しないパスとディレクトリは結果に含まれない。
+async fn head_files(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    tree_sha: &str,
+    paths: impl Iterator<Item = &str>,
+) -> Result<HashMap<String, WorkFile>, GitHubError> {
+    // ディレクトリのパス（ルートは空文字列）ごとの一覧（`None` は存在しない）
+    let mut listings: HashMap<String, Option<Vec<TreeEntry>>> = HashMap::new();
+    let mut files = HashMap::new();
+    for path in paths {
+        if files.contains_key(path) {
+            continue;
+        }
+        let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+        let mut current = String::new();
+        if !listings.contains_key(&current) {
+            let root = client.get_tree(owner, repo, tree_sha, false).await?;
+            listings.insert(current.clone(), Some(root.tree));
+        }
+        for component in dir.split('/').filter(|c| !c.is_empty()) {
+            let child = if current.is_empty() {
+                component.to_string()
+            } else {
+                format!("{}/{}", current, component)
+            };
+            if !listings.contains_key(&child) {
+                let sha = listings[&current]
+                    .iter()
+                    .flatten()
+                    .find(|e| e.path == component && e.entry_type == ObjectType::Tree)
+                    .map(|e| e.sha.clone());
+                let listing = match sha {
+                    Some(sha) => Some(client.get_tree(owner, repo, &sha, false).await?.tree),
+                    None => None,
+                };
+                listings.insert(child.clone(), listing);
+            }
+            current = child;
+        }
+        let entry = listings[&current]
+            .iter()
+            .flatten()
+            .find(|e| e.path == name && e.entry_type != ObjectType::Tree);
+        if let Some(entry) = entry {
+            let file = WorkFile::Head {
+                origin: path.to_string(),
+                sha: entry.sha.clone(),
+                mode: entry.mode,
+            };
+            files.insert(path.to_string(), file);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::GitHubClient;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    fn hunks(diff: &str) -> Vec<Hunk> {
+        Patch::parse(diff).unwrap().files.remove(0).hunks
+    }
+
+    const GIT_DIFF: &str = r#"From 5a7c1f Mon Sep 17 00:00:00 2001
+Subject: [PATCH] Generated changes
+
+---
+ src/lib.rs | 2 +-
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    println!("hello");
++    println!("hello, world");
+ }
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index 3333333..0000000
+diff --git a/draft.md b/final.md
+similarity index 100%
+rename from draft.md
+rename to final.md
+diff --git a/new.sh b/new.sh
+new file mode 100755
+index 0000000..4444444
+--- /dev/null
++++ b/new.sh
+@@ -0,0 +1,2 @@
++#!/bin/sh
++echo hi
+-- 
+2.43.0
+"#;
+
+    #[test]
+    fn test_parse_git_diff() {
+        let patch = Patch::parse(GIT_DIFF).unwrap();
+        assert_eq!(patch.files.len(), 4);
+
+        let modified = &patch.files[0];
+        assert_eq!(modified.old_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(modified.new_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(
+            modified.hunks[0].lines,
+            [
+                HunkLine::Context("fn main() {".to_string()),
+                HunkLine::Delete("    println!(\"hello\");".to_string()),
+                HunkLine::Insert("    println!(\"hello, world\");".to_string()),
+                HunkLine::Context("}".to_string()),
+            ]
+        );
+
+        let deleted = &patch.files[1];
+        assert_eq!(deleted.new_path, None);
+        assert_eq!(deleted.path(), "old.txt");
+
+        let renamed = &patch.files[2];
+        assert_eq!(renamed.old_path.as_deref(), Some("draft.md"));
+        assert_eq!(renamed.new_path.as_deref(), Some("final.md"));
+        assert!(renamed.hunks.is_empty());
+
+        let added = &patch.files[3];
+        assert_eq!(added.old_path, None);
+        assert_eq!(added.new_mode, Some(FileMode::Executable));
+        assert_eq!(added.hunks[0].new_lines, 2);
+    }
+
+    #[test]
+    fn test_parse_quoted_paths_and_missing_newline() {
+        let patch = Patch::parse(
+            "diff --git \"a/docs/\\346\\227\\245\\346\\234\\254.md\" \"b/docs/\\346\\227\\245\\346\\234\\254.md\"\n\
+             --- \"a/docs/\\346\\227\\245\\346\\234\\254.md\"\n\
+             +++ \"b/docs/\\346\\227\\245\\346\\234\\254.md\"\n\
+             @@ -1 +1 @@\n\
+             -old\n\
+             \\ No newline at end of file\n\
+             +new\n",
+        )
+        .unwrap();
+        let file = &patch.files[0];
+        assert_eq!(file.path(), "docs/日本.md");
+        assert!(file.hunks[0].old_missing_newline);
+        assert!(!file.hunks[0].new_missing_newline);
+
+        let plain = Patch::parse(
+            "--- a/my file.txt\t2024-01-01 00:00:00\n+++ b/my file.txt\t2024-01-02 00:00:00\n@@ -1 +1 @@\n-a\n+b\n",
+        )
+        .unwrap();
+        assert_eq!(plain.files[0].path(), "my file.txt");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_patches() {
+        for text in [
+            "not a patch",
+            "@@ -1 +1 @@\n-a\n+b\n",
+            "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n-a\n+b\n",
+            "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n-a\n*b\n",
+            "--- a/x\n+++ b/x\n@@ -x +1 @@\n-a\n+b\n",
+        ] {
+            assert!(
+                matches!(Patch::parse(text), Err(GitHubError::InvalidRequestError(_))),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_hunks_exact_and_offset() {
+        let diff =
+            "--- a/x\n+++ b/x\n@@ -2,3 +2,3 @@\n b\n-c\n+C\n d\n@@ -7,3 +7,4 @@\n g\n h\n+h2\n i\n";
+        let text = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let (result, reports) = apply_hunks("x", text, &hunks(diff), DEFAULT_MAX_FUZZ);
+        assert_eq!(result, "a\nb\nC\nd\ne\nf\ng\nh\nh2\ni\n");
+        assert!(reports
+            .iter()
+            .all(|r| r.as_ref().is_ok_and(|h| h.offset == 0)));
+
+        // 差分の作成後に先頭へ2行追加されたファイル
+        let moved = format!("0\n00\n{}", text);
+        let (result, reports) = apply_hunks("x", &moved, &hunks(diff), DEFAULT_MAX_FUZZ);
+        assert_eq!(
+            result,
+            format!("0\n00\n{}", "a\nb\nC\nd\ne\nf\ng\nh\nh2\ni\n")
+        );
+        let second = reports[1].as_ref().unwrap();
+        assert_eq!((second.line, second.offset, second.fuzz), (9, 2, 0));
+    }
+
+    #[test]
+    fn test_apply_hunks_with_fuzz_and_failure() {
+        let diff = "--- a/x\n+++ b/x\n@@ -1,5 +1,5 @@\n a\n b\n-c\n+C\n d\n e\n";
+        // 先頭のコンテキストが変更されている
+        let (result, reports) =
+            apply_hunks("x", "A\nb\nc\nd\ne\nf\n", &hunks(diff), DEFAULT_MAX_FUZZ);
+        assert_eq!(result, "A\nb\nC\nd\ne\nf\n");
+        assert_eq!(reports[0].as_ref().unwrap().fuzz, 1);
+
+        let (_, reports) = apply_hunks("x", "A\nb\nc\nd\ne\nf\n", &hunks(diff), 0);
+        let failure = reports[0].as_ref().unwrap_err();
+        assert_eq!(failure.hunk, Some(1));
+        assert_eq!(
+            failure.to_string(),
+            "x: hunk #1: context not found near line 1 (fuzz up to 0)"
+        );
+
+        let (_, reports) = apply_hunks("x", "a\nb\nX\nd\ne\n", &hunks(diff), DEFAULT_MAX_FUZZ);
+        assert!(reports[0].is_err());
+    }
+
+    #[test]
+    fn test_apply_hunks_newline_at_end_and_crlf() {
+        let diff = "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n";
+        let (result, _) = apply_hunks("x", "a\nb", &hunks(diff), DEFAULT_MAX_FUZZ);
+        assert_eq!(result, "a\nb\n");
+
+        let diff = "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n a\r\n-b\r\n+c\r\n";
+        let (result, _) = apply_hunks("x", "a\r\nb\r\n", &hunks(diff), DEFAULT_MAX_FUZZ);
+        assert_eq!(result, "a\r\nc\r\n");
+
+        let diff =
+            "--- /dev/null\n+++ b/x\n@@ -0,0 +1,2 @@\n+a\n+b\n\\ No newline at end of file\n";
+        let (result, _) = apply_hunks("x", "", &hunks(diff), DEFAULT_MAX_FUZZ);
+        assert_eq!(result, "a\nb");
+    }
+
+    fn tree_json(sha: &str, entries: serde_json::Value) -> String {
+        json!({"sha": sha, "url": "https://api.github.com/t", "tree": entries, "truncated": false})
+            .to_string()
+    }
+
+    async fn branch_server() -> mockito::ServerGuard {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/main")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "ref": "refs/heads/main",
+                    "url": "https://api.github.com/r",
+                    "object": {"sha": "head1", "type": "commit", "url": "https://api.github.com/c"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/git/commits/head1")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "sha": "head1",
+                    "url": "https://api.github.com/c",
+                    "message": "Initial",
+                    "tree": {"sha": "7ree1", "url": "https://api.github.com/t"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/git/trees/7ree1")
+            .with_status(200)
+            .with_body(tree_json(
+                "7ree1",
+                json!([
+                    {"path": "src", "mode": "040000", "type": "tree", "sha": "7ree2"},
+                    {"path": "old.txt", "mode": "100644", "type": "blob", "sha": "b2", "size": 3},
+                    {"path": "draft.md", "mode": "100644", "type": "blob", "sha": "b3", "size": 9}
+                ]),
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/git/trees/7ree2")
+            .with_status(200)
+            .with_body(tree_json(
+                "7ree2",
+                json!([
+                    {"path": "lib.rs", "mode": "100644", "type": "blob", "sha": "b1", "size": 40}
+                ]),
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/git/blobs/b1")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "sha": "b1",
+                    "url": "https://api.github.com/b1",
+                    "content": "Zm4gbWFpbigpIHsKICAgIHByaW50bG4hKCJoZWxsbyIpOwp9Cg==\n",
+                    "encoding": "base64"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_commits_changes() {
+        let mut server = branch_server().await;
+        for (content, sha) in [
+            (
+                "Zm4gbWFpbigpIHsKICAgIHByaW50bG4hKCJoZWxsbywgd29ybGQiKTsKfQo=",
+                "b4",
+            ),
+            ("IyEvYmluL3NoCmVjaG8gaGkK", "b5"),
+        ] {
+            server
+                .mock("POST", "/repos/owner/repo/git/blobs")
+                .match_body(Matcher::PartialJson(json!({ "content": content })))
+                .with_status(201)
+                .with_body(json!({"sha": sha, "url": "https://api.github.com/x"}).to_string())
+                .create_async()
+                .await;
+        }
+        let tree = server
+            .mock("POST", "/repos/owner/repo/git/trees")
+            .match_body(Matcher::Json(json!({
+                "base_tree": "7ree1",
+                "tree": [
+                    {"path": "draft.md", "mode": "100644", "type": "blob", "sha": null},
+                    {"path": "final.md", "mode": "100644", "type": "blob", "sha": "b3"},
+                    {"path": "src/lib.rs", "mode": "100644", "type": "blob", "sha": "b4"},
+                    {"path": "old.txt", "mode": "100644", "type": "blob", "sha": null},
+                    {"path": "new.sh", "mode": "100755", "type": "blob", "sha": "b5"}
+                ]
+            })))
+            .with_status(201)
+            .with_body(r#"{"sha": "7ree3", "url": "https://api.github.com/x", "tree": []}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .match_body(Matcher::PartialJson(
+                json!({"tree": "7ree3", "parents": ["head1"]}),
+            ))
+            .with_status(201)
+            .with_body(
+                json!({
+                    "sha": "c2",
+                    "url": "https://api.github.com/c2",
+                    "message": "Apply",
+                    "tree": {"sha": "7ree3", "url": "https://api.github.com/t"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let update = server
+            .mock("PATCH", "/repos/owner/repo/git/refs/heads/main")
+            .match_body(Matcher::Json(json!({"sha": "c2", "force": false})))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "ref": "refs/heads/main",
+                    "url": "https://api.github.com/r",
+                    "object": {"sha": "c2", "type": "commit", "url": "https://api.github.com/c"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+        let result = client
+            .commit_builder("owner", "repo", "main")
+            .message("Apply")
+            .apply_patch(&Patch::parse(GIT_DIFF).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(result.sha, "c2");
+        assert_eq!(result.hunks.len(), 2);
+        assert_eq!(result.inexact_hunks().count(), 0);
+        tree.assert_async().await;
+        update.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_reports_failures_without_committing() {
+        let mut server = branch_server().await;
+        let commit = server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let diff = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,3 @@\n fn start() {\n-    println!(\"bye\");\n+    println!(\"hi\");\n }\n\
+                    --- /dev/null\n+++ b/old.txt\n@@ -0,0 +1 @@\n+x\n\
+                    --- a/missing.txt\n+++ b/missing.txt\n@@ -1 +1 @@\n-a\n+b\n";
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+        let error = client
+            .commit_builder("owner", "repo", "main")
+            .message("Apply")
+            .apply_patch(&Patch::parse(diff).unwrap())
+            .await
+            .unwrap_err();
+
+        let GitHubError::PatchFailed(failures) = error else {
+            panic!("unexpected error: {:?}", error);
+        };
+        let messages: Vec<String> = failures.iter().map(|f| f.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "src/lib.rs: hunk #1: context not found near line 1 (fuzz up to 2)",
+                "old.txt: file already exists",
+                "missing.txt: file does not exist",
+            ]
+        );
+        commit.assert_async().await;
+    }
+}
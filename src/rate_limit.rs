@@ -0,0 +1,269 @@
+use crate::client::GitHubClient;
+use crate::errors::{header_u64, GitHubError};
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// レスポンスヘッダーから読み取ったレート制限の残量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    pub used: Option<u64>,
+    pub reset: DateTime<Utc>,
+    /// `core`、`search`、`graphql` などのリソース名
+    pub resource: Option<String>,
+}
+
+impl RateLimit {
+    /// `x-ratelimit-*` ヘッダーから読み取る（ヘッダーが無い場合は `None`）
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let limit = header_u64(headers, "x-ratelimit-limit")?;
+        let remaining = header_u64(headers, "x-ratelimit-remaining")?;
+        let reset = header_u64(headers, "x-ratelimit-reset")
+            .and_then(|secs| Utc.timestamp_opt(secs as i64, 0).single())?;
+        Some(Self {
+            limit,
+            remaining,
+            used: header_u64(headers, "x-ratelimit-used"),
+            reset,
+            resource: headers
+                .get("x-ratelimit-resource")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+        })
+    }
+
+    /// リセットまでの残り時間（既にリセット済みならゼロ）
+    pub fn time_until_reset(&self) -> Duration {
+        (self.reset - Utc::now()).to_std().unwrap_or_default()
+    }
+}
+
+/// レート制限に達したときの振る舞い
+///
+/// デフォルトでは待機せず、そのまま `RateLimitError` / `SecondaryRateLimitError` を返す。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    /// プライマリ制限に達したらリセットまで待機する
+    pub wait_for_reset: bool,
+    /// セカンダリ制限の 403/429 を受けたら待機して再送する
+    pub backoff_on_secondary: bool,
+    /// これより長く待つ必要がある場合は待たずにエラーを返す
+    pub max_wait: Duration,
+    /// `retry-after` が無いセカンダリ制限で待機する時間
+    pub secondary_backoff: Duration,
+    /// 1リクエストあたりの最大待機回数
+    pub max_retries: u32,
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self {
+            wait_for_reset: false,
+            backoff_on_secondary: false,
+            max_wait: Duration::from_secs(15 * 60),
+            secondary_backoff: Duration::from_secs(60),
+            max_retries: 3,
+        }
+    }
+}
+
+impl RateLimitPolicy {
+    /// プライマリ・セカンダリの両方で待機するポリシー
+    pub fn wait() -> Self {
+        Self {
+            wait_for_reset: true,
+            backoff_on_secondary: true,
+            ..Self::default()
+        }
+    }
+
+    /// エラーに対して待機すべき時間を返す（待機しない場合は `None`）
+    pub(crate) fn wait_for(&self, error: &GitHubError) -> Option<Duration> {
+        let wait = match error {
+            GitHubError::RateLimitError { reset, .. } if self.wait_for_reset => reset
+                .map(|r| (r - Utc::now()).to_std().unwrap_or_default())
+                .unwrap_or(self.secondary_backoff),
+            GitHubError::SecondaryRateLimitError { retry_after, .. }
+                if self.backoff_on_secondary =>
+            {
+                retry_after.unwrap_or(self.secondary_backoff)
+            }
+            _ => return None,
+        };
+        (wait <= self.max_wait).then_some(wait)
+    }
+}
+
+/// `/rate_limit` の1リソース分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitResource {
+    pub limit: u64,
+    pub remaining: u64,
+    #[serde(default)]
+    pub used: u64,
+    /// リセット時刻（UNIX 秒）
+    pub reset: i64,
+}
+
+/// `/rate_limit` のリソース別の残量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitResources {
+    pub core: RateLimitResource,
+    #[serde(default)]
+    pub search: Option<RateLimitResource>,
+    #[serde(default)]
+    pub graphql: Option<RateLimitResource>,
+    #[serde(default)]
+    pub code_search: Option<RateLimitResource>,
+}
+
+/// `GET /rate_limit` のレスポンス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitOverview {
+    pub resources: RateLimitResources,
+    pub rate: RateLimitResource,
+}
+
+impl GitHubClient {
+    /// 直近のレスポンスから読み取ったレート制限の残量
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 直近の GraphQL レスポンスから読み取ったレート制限（ポイント）の残量
+    pub fn graphql_rate_limit(&self) -> Option<RateLimit> {
+        self.graphql_rate_limit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// `/rate_limit` エンドポイントから残量を取得する（このリクエストは制限を消費しない）
+    pub async fn get_rate_limit(&self) -> Result<RateLimitOverview, GitHubError> {
+        Ok(self.get("/rate_limit").await?.json().await?)
+    }
+
+    pub(crate) fn record_rate_limit(&self, headers: &HeaderMap) {
+        if let Some(limit) = RateLimit::from_headers(headers) {
+            // GraphQL はポイント制で REST とは別に集計される
+            let slot = if limit.resource.as_deref() == Some("graphql") {
+                &self.graphql_rate_limit
+            } else {
+                &self.rate_limit
+            };
+            *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(limit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_rate_limit_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(RateLimit::from_headers(&headers).is_none());
+
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("5000"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("4999"));
+        headers.insert("x-ratelimit-used", HeaderValue::from_static("1"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1700000000"));
+        headers.insert("x-ratelimit-resource", HeaderValue::from_static("core"));
+
+        let limit = RateLimit::from_headers(&headers).unwrap();
+        assert_eq!(limit.limit, 5000);
+        assert_eq!(limit.remaining, 4999);
+        assert_eq!(limit.used, Some(1));
+        assert_eq!(limit.reset.timestamp(), 1_700_000_000);
+        assert_eq!(limit.resource.as_deref(), Some("core"));
+        assert_eq!(limit.time_until_reset(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_policy_wait_for() {
+        let secondary = GitHubError::SecondaryRateLimitError {
+            retry_after: Some(Duration::from_secs(30)),
+            message: "slow down".to_string(),
+        };
+        assert_eq!(RateLimitPolicy::default().wait_for(&secondary), None);
+        assert_eq!(
+            RateLimitPolicy::wait().wait_for(&secondary),
+            Some(Duration::from_secs(30))
+        );
+
+        let primary = GitHubError::RateLimitError {
+            limit: Some(5000),
+            reset: Some(Utc::now() + chrono::Duration::hours(2)),
+            message: "API rate limit exceeded".to_string(),
+        };
+        assert_eq!(RateLimitPolicy::wait().wait_for(&primary), None);
+    }
+
+    #[tokio::test]
+    async fn test_waits_and_retries_on_rate_limit() {
+        let reset = Utc::now().timestamp().to_string();
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/user")
+            .with_status(403)
+            .with_header("x-ratelimit-limit", "60")
+            .with_header("x-ratelimit-remaining", "0")
+            .with_header("x-ratelimit-reset", &reset)
+            .with_body(r#"{"message": "API rate limit exceeded"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/user")
+            .with_status(200)
+            .with_header("x-ratelimit-limit", "5000")
+            .with_header("x-ratelimit-remaining", "4999")
+            .with_header("x-ratelimit-reset", &reset)
+            .with_body(r#"{"login": "octocat", "id": 1, "node_id": "MDQ6VXNlcjE="}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string())
+            .with_rate_limit_policy(RateLimitPolicy::wait());
+        client.base_url = server.url();
+
+        let user = client.get_authenticated_user().await.unwrap();
+        assert_eq!(user.login, "octocat");
+        assert_eq!(client.rate_limit().unwrap().remaining, 4999);
+        limited.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_returns_rate_limit_error_by_default() {
+        let mut server = mockito::Server::new_async().await;
+        let _limited = server
+            .mock("GET", "/user")
+            .with_status(429)
+            .with_header("retry-after", "1")
+            .with_body(r#"{"message": "You have exceeded a secondary rate limit"}"#)
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let error = client.get_authenticated_user().await.unwrap_err();
+        assert!(matches!(
+            error,
+            GitHubError::SecondaryRateLimitError {
+                retry_after: Some(_),
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,392 @@
+use crate::client::{encode_path, GitHubClient};
+use crate::errors::GitHubError;
+use crate::models::GitRef;
+use serde_json::json;
+use tracing::info;
+
+/// `git check-ref-format` の規則でリファレンス名を検証する
+///
+/// `refs/heads/main` のような完全な名前を渡す。
+pub fn check_ref_format(name: &str) -> Result<(), GitHubError> {
+    let invalid = |reason: &str| {
+        Err(GitHubError::InvalidRequestError(format!(
+            "Invalid ref name {:?}: {}",
+            name, reason
+        )))
+    };
+
+    if name.is_empty() || name == "@" {
+        return invalid("empty or '@'");
+    }
+    if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+        return invalid("empty path component");
+    }
+    if !name.contains('/') {
+        return invalid("must contain at least one '/'");
+    }
+    if name.ends_with('.') {
+        return invalid("cannot end with '.'");
+    }
+    if name.contains("..") || name.contains("@{") {
+        return invalid("cannot contain '..' or '@{'");
+    }
+    if let Some(c) = name.chars().find(|c| {
+        c.is_ascii_control() || matches!(c, ' ' | '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+    }) {
+        return invalid(&format!("cannot contain {:?}", c));
+    }
+    if name
+        .split('/')
+        .any(|component| component.starts_with('.') || component.ends_with(".lock"))
+    {
+        return invalid("components cannot start with '.' or end with '.lock'");
+    }
+    Ok(())
+}
+
+/// ブランチ名を検証する（`git check-ref-format --branch` 相当）
+pub fn check_branch_name(branch: &str) -> Result<(), GitHubError> {
+    if branch.starts_with('-') || branch == "HEAD" {
+        return Err(GitHubError::InvalidRequestError(format!(
+            "Invalid branch name {:?}",
+            branch
+        )));
+    }
+    check_ref_format(&format!("refs/heads/{}", branch))
+}
+
+/// `refs/heads/main`・`heads/main` のどちらも API が使う `heads/main` にそろえ、検証する
+fn api_ref(name: &str) -> Result<&str, GitHubError> {
+    let name = name.strip_prefix("refs/").unwrap_or(name);
+    check_ref_format(&format!("refs/{}", name))?;
+    Ok(name)
+}
+
+impl GitHubClient {
+    /// リファレンスを取得する（`heads/main`、`tags/v1.0.0` など）
+    pub async fn get_ref(
+        &self,
+        owner: &str,
+        repo: &str,
+        ref_name: &str,
+    ) -> Result<GitRef, GitHubError> {
+        let path = format!(
+            "/repos/{}/{}/git/ref/{}",
+            owner,
+            repo,
+            encode_path(api_ref(ref_name)?)
+        );
+        Ok(self.get(&path).await?.json().await?)
+    }
+
+    /// タグのリファレンスを取得する
+    ///
+    /// 注釈付きタグの場合、`object` はコミットではなくタグオブジェクトを指す。
+    pub async fn get_tag_ref(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> Result<GitRef, GitHubError> {
+        self.get_ref(owner, repo, &format!("tags/{}", tag)).await
+    }
+
+    /// 名前が `prefix` で始まるリファレンスを全件取得する
+    ///
+    /// `heads/release/` のように指定する。一致するものがなければ空の `Vec` を返す。
+    /// `.`・`..` を含むプレフィックスは `InvalidRequestError` になる。
+    pub async fn list_matching_refs(
+        &self,
+        owner: &str,
+        repo: &str,
+        prefix: &str,
+    ) -> Result<Vec<GitRef>, GitHubError> {
+        let prefix = prefix.strip_prefix("refs/").unwrap_or(prefix);
+        if prefix
+            .split('/')
+            .any(|component| component == "." || component == "..")
+        {
+            return Err(GitHubError::InvalidRequestError(format!(
+                "Invalid ref prefix {:?}: cannot contain '.' or '..' components",
+                prefix
+            )));
+        }
+        let path = format!(
+            "/repos/{}/{}/git/matching-refs/{}",
+            owner,
+            repo,
+            encode_path(prefix)
+        );
+        self.collect_all(&path, Some(100)).await
+    }
+
+    /// すべてのブランチのリファレンスを取得する
+    pub async fn list_branch_refs(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<GitRef>, GitHubError> {
+        self.list_matching_refs(owner, repo, "heads/").await
+    }
+
+    /// すべてのタグのリファレンスを取得する
+    pub async fn list_tag_refs(&self, owner: &str, repo: &str) -> Result<Vec<GitRef>, GitHubError> {
+        self.list_matching_refs(owner, repo, "tags/").await
+    }
+
+    /// リファレンスを作成する（`ref_name` は `refs/heads/...` のような完全な名前）
+    pub async fn create_ref(
+        &self,
+        owner: &str,
+        repo: &str,
+        ref_name: &str,
+        sha: &str,
+    ) -> Result<GitRef, GitHubError> {
+        if !ref_name.starts_with("refs/") {
+            return Err(GitHubError::InvalidRequestError(format!(
+                "Ref name {:?} must start with 'refs/'",
+                ref_name
+            )));
+        }
+        check_ref_format(ref_name)?;
+        let path = format!("/repos/{}/{}/git/refs", owner, repo);
+        let body = json!({ "ref": ref_name, "sha": sha });
+        Ok(self.post(&path, &body).await?.json().await?)
+    }
+
+    /// 軽量タグを作成する
+    pub async fn create_tag_ref(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        sha: &str,
+    ) -> Result<GitRef, GitHubError> {
+        self.create_ref(owner, repo, &format!("refs/tags/{}", tag), sha)
+            .await
+    }
+
+    /// リファレンスの指す先を更新する（`force` が `false` なら fast-forward のみ）
+    pub async fn update_ref(
+        &self,
+        owner: &str,
+        repo: &str,
+        ref_name: &str,
+        sha: &str,
+        force: bool,
+    ) -> Result<GitRef, GitHubError> {
+        let path = format!(
+            "/repos/{}/{}/git/refs/{}",
+            owner,
+            repo,
+            encode_path(api_ref(ref_name)?)
+        );
+        let body = json!({ "sha": sha, "force": force });
+        Ok(self.patch(&path, &body).await?.json().await?)
+    }
+
+    /// リファレンスを削除する
+    pub async fn delete_ref(
+        &self,
+        owner: &str,
+        repo: &str,
+        ref_name: &str,
+    ) -> Result<(), GitHubError> {
+        let ref_name = api_ref(ref_name)?;
+        let path = format!(
+            "/repos/{}/{}/git/refs/{}",
+            owner,
+            repo,
+            encode_path(ref_name)
+        );
+        self.delete(&path).await?;
+        info!(target: "github_client", %owner, %repo, %ref_name, "Ref deleted");
+        Ok(())
+    }
+
+    /// ブランチを削除する（プルリクエストのマージ後の後片付けなど）
+    pub async fn delete_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<(), GitHubError> {
+        self.delete_ref(owner, repo, &format!("heads/{}", branch))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn ref_json(name: &str, sha: &str) -> serde_json::Value {
+        json!({
+            "ref": name,
+            "url": format!("https://api.github.com/repos/owner/repo/git/{}", name),
+            "object": {"sha": sha, "type": "commit", "url": "https://api.github.com/x"}
+        })
+    }
+
+    #[test]
+    fn test_check_ref_format() {
+        for name in [
+            "refs/heads/main",
+            "refs/heads/feature/login",
+            "refs/heads/fix#123",
+            "refs/tags/v1.0.0",
+            "refs/heads/日本語",
+        ] {
+            assert!(check_ref_format(name).is_ok(), "{}", name);
+        }
+        for name in [
+            "",
+            "main",
+            "refs/heads/",
+            "refs//heads",
+            "refs/heads/a..b",
+            "refs/heads/.hidden",
+            "refs/heads/x.lock",
+            "refs/heads/x.",
+            "refs/heads/a b",
+            "refs/heads/a~1",
+            "refs/heads/a^",
+            "refs/heads/a:b",
+            "refs/heads/a?",
+            "refs/heads/a*",
+            "refs/heads/a[b",
+            "refs/heads/a\\b",
+            "refs/heads/a@{1}",
+            "refs/heads/tab\t",
+        ] {
+            assert!(
+                matches!(
+                    check_ref_format(name),
+                    Err(GitHubError::InvalidRequestError(_))
+                ),
+                "{:?}",
+                name
+            );
+        }
+        assert!(check_branch_name("feature/login").is_ok());
+        assert!(check_branch_name("-rf").is_err());
+        assert!(check_branch_name("HEAD").is_err());
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path("heads/feature/login"), "heads/feature/login");
+        assert_eq!(encode_path("heads/fix#123"), "heads/fix%23123");
+        assert_eq!(encode_path("heads/100%"), "heads/100%25");
+        assert_eq!(encode_path("heads/é"), "heads/%C3%A9");
+    }
+
+    #[tokio::test]
+    async fn test_get_ref_encodes_name() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/owner/repo/git/ref/heads/feature/fix%23123")
+            .with_status(200)
+            .with_body(ref_json("refs/heads/feature/fix#123", "aa218f56").to_string())
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let git_ref = client
+            .get_branch_ref("owner", "repo", "feature/fix#123")
+            .await
+            .unwrap();
+        assert_eq!(git_ref.ref_name, "refs/heads/feature/fix#123");
+        mock.assert_async().await;
+
+        let error = client
+            .get_ref("owner", "repo", "heads/../../admin")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_list_matching_refs() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock(
+                "GET",
+                "/repos/owner/repo/git/matching-refs/tags/?per_page=100",
+            )
+            .with_status(200)
+            .with_body(
+                json!([
+                    ref_json("refs/tags/v1.0.0", "aa218f56"),
+                    ref_json("refs/tags/v1.1.0", "bb318f56")
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        let tags = client.list_tag_refs("owner", "repo").await.unwrap();
+        let names: Vec<_> = tags.iter().map(|r| r.ref_name.as_str()).collect();
+        assert_eq!(names, ["refs/tags/v1.0.0", "refs/tags/v1.1.0"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_matching_refs_rejects_dot_components() {
+        let client = GitHubClient::new("test_token".to_string());
+        for prefix in ["../../../../user", "heads/../tags/", "refs/./heads/"] {
+            let error = client
+                .list_matching_refs("owner", "repo", prefix)
+                .await
+                .unwrap_err();
+            assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_refs() {
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("POST", "/repos/owner/repo/git/refs")
+            .match_body(Matcher::Json(
+                json!({"ref": "refs/tags/v2.0.0", "sha": "aa218f56"}),
+            ))
+            .with_status(201)
+            .with_body(ref_json("refs/tags/v2.0.0", "aa218f56").to_string())
+            .create_async()
+            .await;
+        let delete = server
+            .mock(
+                "DELETE",
+                "/repos/owner/repo/git/refs/heads/feature/fix%23123",
+            )
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+
+        client
+            .create_tag_ref("owner", "repo", "v2.0.0", "aa218f56")
+            .await
+            .unwrap();
+        client
+            .delete_branch("owner", "repo", "feature/fix#123")
+            .await
+            .unwrap();
+        create.assert_async().await;
+        delete.assert_async().await;
+
+        let error = client
+            .create_branch("owner", "repo", "bad..name", "aa218f56")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GitHubError::InvalidRequestError(_)));
+    }
+}
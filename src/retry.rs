@@ -0,0 +1,213 @@
+use crate::errors::GitHubError;
+use reqwest::{Method, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// 一時的な失敗に対する再試行ポリシー
+///
+/// 接続エラーはリクエストが送信されていないため常に再試行できる。タイムアウトと
+/// `retry_statuses` に含まれるステータスは、冪等なリクエストか
+/// `retry_non_idempotent` が有効な場合のみ再試行する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最初の試行を含む最大試行回数（1 なら再試行しない）
+    pub max_attempts: u32,
+    /// 1回目の再試行までの待機時間
+    pub initial_backoff: Duration,
+    /// 待機時間の上限
+    pub max_backoff: Duration,
+    /// 待機時間を `[0, backoff]` の範囲でランダムにする（full jitter）
+    pub jitter: bool,
+    /// 再試行するHTTPステータス
+    pub retry_statuses: Vec<StatusCode>,
+    /// POST / PATCH など冪等でないリクエストも再試行する
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 再試行しないポリシー
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// `attempt` 回目（1始まり）の試行が `error` で失敗したときに再試行するか
+    pub fn should_retry(&self, error: &GitHubError, attempt: u32, idempotent: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        let safe = idempotent || self.retry_non_idempotent;
+        match error {
+            GitHubError::HttpError(e) if e.is_connect() => true,
+            GitHubError::HttpError(e) if e.is_timeout() => safe,
+            _ => {
+                safe && error
+                    .status()
+                    .is_some_and(|s| self.retry_statuses.contains(&s))
+            }
+        }
+    }
+
+    /// `attempt` 回目の失敗後に待機する時間（指数バックオフ）
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+}
+
+/// HTTP メソッドが冪等かどうか
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// `[0, 1)` の疑似乱数（ジッター用途なので暗号学的な強度は不要）
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::GitHubClient;
+
+    fn server_error(status: u16) -> GitHubError {
+        GitHubError::ApiError {
+            status_code: status,
+            message: "error".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(&server_error(502), 1, true));
+        assert!(policy.should_retry(&server_error(503), 2, true));
+        assert!(!policy.should_retry(&server_error(503), 3, true));
+        assert!(!policy.should_retry(&server_error(502), 1, false));
+        assert!(!policy.should_retry(&server_error(400), 1, true));
+        assert!(!policy.should_retry(&GitHubError::NotFoundError("x".into()), 1, true));
+
+        let policy = RetryPolicy {
+            retry_non_idempotent: true,
+            ..RetryPolicy::default()
+        };
+        assert!(policy.should_retry(&server_error(502), 1, false));
+        assert!(!RetryPolicy::none().should_retry(&server_error(502), 1, true));
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+
+        let jittered = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        assert!(jittered.backoff(3) <= Duration::from_millis(350));
+    }
+
+    #[tokio::test]
+    async fn test_retries_idempotent_blob_creation() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("POST", "/repos/owner/repo/git/blobs")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let created = server
+            .mock("POST", "/repos/owner/repo/git/blobs")
+            .with_status(201)
+            .with_body(r#"{"sha": "b10b", "url": "https://api.github.com/x"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut client =
+            GitHubClient::new("test_token".to_string()).with_retry_policy(RetryPolicy {
+                initial_backoff: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            });
+        client.base_url = server.url();
+
+        let blob = client
+            .create_blob("owner", "repo", "content")
+            .await
+            .unwrap();
+        assert_eq!(blob.sha, "b10b");
+        unavailable.assert_async().await;
+        created.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_pull_request_creation() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("POST", "/repos/owner/repo/pulls")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut client =
+            GitHubClient::new("test_token".to_string()).with_retry_policy(RetryPolicy {
+                initial_backoff: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            });
+        client.base_url = server.url();
+
+        let error = client
+            .create_pull_request("owner", "repo", "main", "feature", "title", "body")
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_GATEWAY));
+        unavailable.assert_async().await;
+    }
+}
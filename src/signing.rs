@@ -0,0 +1,284 @@
+//! コミットのローカル署名
+//!
+//! GitHub がハッシュするのと同じ形式で生のコミットオブジェクトを組み立て、
+//! SSH 鍵（sshsig 形式）または OpenPGP 鍵で署名する。git コマンドは使わない。
+//!
+//! ```no_run
+//! # async fn run(client: &github::client::GitHubClient) -> Result<(), github::errors::GitHubError> {
+//! use github::models::{GitUser, NewCommit};
+//! use github::signing::SshSigner;
+//!
+//! let signer = SshSigner::from_file("/etc/release-bot/id_ed25519", None)?;
+//! let commit = NewCommit::new("Release v1.2.0", "691272480426f78a0138979dd3ce63b77f706feb")
+//!     .parent("6dcb09b5b57875f334f61aebed695e2e4193db5e")
+//!     .author(GitUser::new("Release Bot", "release-bot@example.com"));
+//! let commit = client
+//!     .create_signed_commit("owner", "repo", commit, &signer)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod pgp;
+pub mod ssh;
+
+pub use pgp::PgpSigner;
+pub use ssh::SshSigner;
+
+use crate::client::GitHubClient;
+use crate::errors::GitHubError;
+use crate::models::{Commit, GitUser, NewCommit};
+use chrono::{DateTime, TimeZone, Utc};
+use sha1::{Digest, Sha1};
+use tracing::{info, warn};
+
+/// コミットオブジェクトに署名する鍵
+pub trait CommitSigner: Send + Sync {
+    /// `payload` に対する ASCII armor 形式の署名を返す
+    fn sign(&self, payload: &[u8]) -> Result<String, GitHubError>;
+}
+
+/// 署名対象となる生のコミットオブジェクト（`gpgsig` ヘッダーを除く）を組み立てる
+///
+/// 作者・コミッターの日時は省略できない。API は日時を UTC として保存するため、
+/// タイムゾーンは常に `+0000` になる。
+pub fn commit_payload(commit: &NewCommit) -> Result<String, GitHubError> {
+    let author = commit.author.as_ref().ok_or_else(|| {
+        GitHubError::InvalidRequestError("Signed commits require an explicit author".to_string())
+    })?;
+    let committer = commit.committer.as_ref().unwrap_or(author);
+
+    let mut payload = format!("tree {}\n", commit.tree);
+    for parent in &commit.parents {
+        payload.push_str(&format!("parent {}\n", parent));
+    }
+    payload.push_str(&identity_line("author", author)?);
+    payload.push_str(&identity_line("committer", committer)?);
+    payload.push('\n');
+    payload.push_str(&commit.message);
+    Ok(payload)
+}
+
+fn identity_line(kind: &str, user: &GitUser) -> Result<String, GitHubError> {
+    let date = user.date.ok_or_else(|| {
+        GitHubError::InvalidRequestError(format!("Signed commits require a {} date", kind))
+    })?;
+    // git はこれらの文字を名前・メールアドレスから取り除くため、署名対象と一致しなくなる
+    let invalid = |s: &str| s.contains(['<', '>', '\n']) || s.trim() != s;
+    if invalid(&user.name) || invalid(&user.email) {
+        return Err(GitHubError::InvalidRequestError(format!(
+            "Invalid {} identity for a signed commit: {} <{}>",
+            kind, user.name, user.email
+        )));
+    }
+    Ok(format!(
+        "{} {} <{}> {} +0000\n",
+        kind,
+        user.name,
+        user.email,
+        date.timestamp()
+    ))
+}
+
+/// コミットに署名し、`signature` を設定する
+///
+/// 省略された日時は現在時刻（秒単位）で、コミッターは作者で補われる。
+/// GitHub 側で同じオブジェクトが作られるよう、補った値も送信内容に含まれる。
+pub fn sign_commit(commit: &mut NewCommit, signer: &dyn CommitSigner) -> Result<(), GitHubError> {
+    let now = Utc
+        .timestamp_opt(Utc::now().timestamp(), 0)
+        .single()
+        .unwrap_or_else(Utc::now);
+    let author = commit.author.as_mut().ok_or_else(|| {
+        GitHubError::InvalidRequestError("Signed commits require an explicit author".to_string())
+    })?;
+    truncate_date(author, now);
+    let mut committer = commit.committer.take().unwrap_or_else(|| author.clone());
+    truncate_date(&mut committer, now);
+    commit.committer = Some(committer);
+
+    let payload = commit_payload(commit)?;
+    commit.signature = Some(signer.sign(payload.as_bytes())?);
+    Ok(())
+}
+
+fn truncate_date(user: &mut GitUser, now: DateTime<Utc>) {
+    let date = user.date.unwrap_or(now);
+    user.date = Utc.timestamp_opt(date.timestamp(), 0).single();
+}
+
+/// 署名を含むコミットオブジェクトの SHA-1 を計算する
+///
+/// GitHub が返すコミットの SHA と一致すれば、署名対象も一致している。
+pub fn commit_sha(commit: &NewCommit) -> Result<String, GitHubError> {
+    let payload = commit_payload(commit)?;
+    let object = match &commit.signature {
+        Some(signature) => {
+            let (headers, message) = payload.split_once("\n\n").unwrap_or((&payload, ""));
+            format!(
+                "{}\ngpgsig {}\n\n{}",
+                headers,
+                signature.trim_end_matches('\n').replace('\n', "\n "),
+                message
+            )
+        }
+        None => payload,
+    };
+    let mut hasher = Sha1::new();
+    hasher.update(format!("commit {}\0", object.len()));
+    hasher.update(object.as_bytes());
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+impl GitHubClient {
+    /// コミットをローカルの鍵で署名してから作成する
+    ///
+    /// 作者は必須。GitHub が返した SHA がローカルで計算したものと異なる場合は、
+    /// 署名が検証されないため警告を出す。
+    pub async fn create_signed_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        mut commit: NewCommit,
+        signer: &dyn CommitSigner,
+    ) -> Result<Commit, GitHubError> {
+        sign_commit(&mut commit, signer)?;
+        let expected_sha = commit_sha(&commit)?;
+        let created = self.create_commit_with(owner, repo, &commit).await?;
+        if created.sha == expected_sha {
+            info!(target: "github_client", sha = %created.sha, "Signed commit created");
+        } else {
+            warn!(
+                target: "github_client",
+                sha = %created.sha,
+                expected = %expected_sha,
+                "Created commit differs from the signed payload; the signature will not verify"
+            );
+        }
+        Ok(created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    struct FixedSigner;
+
+    impl CommitSigner for FixedSigner {
+        fn sign(&self, payload: &[u8]) -> Result<String, GitHubError> {
+            Ok(format!(
+                "-----BEGIN SSH SIGNATURE-----\n{}\n-----END SSH SIGNATURE-----\n",
+                payload.len()
+            ))
+        }
+    }
+
+    fn date(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_commit_payload() {
+        let commit = NewCommit::new("Merge feature\n\nDetails", "t1")
+            .parents(["p1", "p2"])
+            .author(GitUser::new("Mona Octocat", "octocat@github.com").with_date(date(1700000000)))
+            .committer(GitUser::new("Bot", "bot@example.com").with_date(date(1700000060)));
+        assert_eq!(
+            commit_payload(&commit).unwrap(),
+            "tree t1\nparent p1\nparent p2\n\
+             author Mona Octocat <octocat@github.com> 1700000000 +0000\n\
+             committer Bot <bot@example.com> 1700000060 +0000\n\
+             \nMerge feature\n\nDetails"
+        );
+    }
+
+    #[test]
+    fn test_commit_payload_requires_identity() {
+        let commit = NewCommit::new("msg", "t1");
+        assert!(matches!(
+            commit_payload(&commit),
+            Err(GitHubError::InvalidRequestError(_))
+        ));
+
+        let commit = NewCommit::new("msg", "t1")
+            .author(GitUser::new("Mona <admin>", "a@example.com").with_date(date(0)));
+        assert!(commit_payload(&commit).is_err());
+    }
+
+    #[test]
+    fn test_sign_commit_fills_dates_and_committer() {
+        let mut commit =
+            NewCommit::new("msg", "t1").author(GitUser::new("Mona", "mona@example.com"));
+        sign_commit(&mut commit, &FixedSigner).unwrap();
+
+        let author = commit.author.as_ref().unwrap();
+        assert!(author.date.is_some());
+        assert_eq!(commit.committer.as_ref(), Some(author));
+        assert!(commit.signature.unwrap().contains("SSH SIGNATURE"));
+    }
+
+    #[test]
+    fn test_commit_sha_matches_git() {
+        // `git hash-object -t commit` で計算した値
+        let commit = NewCommit::new(
+            "Initial commit\n",
+            "4b825dc642cb6eb9a060e54bf8d69288fbee4904",
+        )
+        .author(GitUser::new("Mona", "mona@example.com").with_date(date(1700000000)));
+        assert_eq!(
+            commit_sha(&commit).unwrap(),
+            "ed04f9bce2e898142d1e5f46b5ccdf8d365a1ec3"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_signed_commit_sends_signature() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/repos/owner/repo/git/commits")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::PartialJson(json!({
+                    "message": "Release",
+                    "tree": "t1",
+                    "parents": ["p1"],
+                    "author": {"name": "Bot", "email": "bot@example.com", "date": "2023-11-14T22:13:20Z"},
+                    "committer": {"name": "Bot", "email": "bot@example.com", "date": "2023-11-14T22:13:20Z"},
+                })),
+                Matcher::Regex("BEGIN SSH SIGNATURE".to_string()),
+            ]))
+            .with_status(201)
+            .with_body(
+                json!({
+                    "sha": "c1",
+                    "url": "https://api.github.com/repos/owner/repo/git/commits/c1",
+                    "message": "Release",
+                    "tree": {"sha": "t1", "url": "https://api.github.com/t1"}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = GitHubClient::builder()
+            .token("test")
+            .base_url(server.url())
+            .build()
+            .unwrap();
+        let commit = NewCommit::new("Release", "t1")
+            .parent("p1")
+            .author(GitUser::new("Bot", "bot@example.com").with_date(date(1700000000)));
+        let created = client
+            .create_signed_commit("owner", "repo", commit, &FixedSigner)
+            .await
+            .unwrap();
+        assert_eq!(created.sha, "c1");
+        mock.assert_async().await;
+    }
+}
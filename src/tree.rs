@@ -0,0 +1,351 @@
+use crate::client::GitHubClient;
+use crate::errors::GitHubError;
+use crate::models::{ObjectType, Tree, TreeEntry};
+use futures::stream::{self, Stream};
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use std::collections::VecDeque;
+use tracing::{debug, warn};
+
+/// 未取得のサブツリー（SHA と、そのツリーのパスに付ける接頭辞）
+type PendingTree = (String, String);
+
+impl GitHubClient {
+    /// ツリーを取得する
+    ///
+    /// `recursive` が `true` の場合はサブツリー以下のエントリもすべて含み、`path` は
+    /// ルートからの相対パスになる。API の上限（10万件・7MB）を超えて `truncated` に
+    /// なった場合は、サブツリーを1つずつ辿って残りを取得するため、結果は常に完全になる。
+    pub async fn get_tree(
+        &self,
+        owner: &str,
+        repo: &str,
+        tree_sha: &str,
+        recursive: bool,
+    ) -> Result<Tree, GitHubError> {
+        let tree = self.fetch_tree(owner, repo, tree_sha, recursive).await?;
+        if !recursive || !tree.truncated {
+            return Ok(tree);
+        }
+
+        warn!(
+            target: "github_client",
+            %tree_sha,
+            "Recursive tree is truncated; walking subtrees"
+        );
+        let root = self.fetch_tree(owner, repo, tree_sha, false).await?;
+        let mut pending: Vec<PendingTree> = subtrees(&root.tree, "").collect();
+        let mut entries = root.tree;
+        while let Some(subtree) = pending.pop() {
+            entries.extend(self.expand_tree(owner, repo, subtree, &mut pending).await?);
+        }
+        Ok(Tree {
+            tree: entries,
+            truncated: false,
+            ..tree
+        })
+    }
+
+    /// ツリー以下のエントリを遅延して辿る `TreeWalk` を作る
+    ///
+    /// ```no_run
+    /// # async fn run(client: &github::client::GitHubClient) -> Result<(), github::errors::GitHubError> {
+    /// use futures::TryStreamExt;
+    ///
+    /// let head = client.get_base_branch_sha("owner", "repo", "main").await?;
+    /// let tree_sha = client.get_latest_tree_sha("owner", "repo", &head).await?;
+    /// let mut files = std::pin::pin!(client
+    ///     .walk_tree("owner", "repo", &tree_sha)
+    ///     .glob("src/**/*.rs")?
+    ///     .into_stream());
+    /// while let Some(entry) = files.try_next().await? {
+    ///     println!("{} ({} bytes)", entry.path, entry.size.unwrap_or(0));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn walk_tree(&self, owner: &str, repo: &str, tree_sha: &str) -> TreeWalk<'_> {
+        TreeWalk {
+            client: self,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            tree_sha: tree_sha.to_string(),
+            globs: Vec::new(),
+        }
+    }
+
+    async fn fetch_tree(
+        &self,
+        owner: &str,
+        repo: &str,
+        tree_sha: &str,
+        recursive: bool,
+    ) -> Result<Tree, GitHubError> {
+        let mut path = format!("/repos/{}/{}/git/trees/{}", owner, repo, tree_sha);
+        if recursive {
+            path.push_str("?recursive=1");
+        }
+        Ok(self.get(&path).await?.json().await?)
+    }
+
+    /// サブツリーを1つ取得し、パスに接頭辞を付けたエントリを返す
+    ///
+    /// まず再帰的に取得し、`truncated` の場合は直下のエントリだけを返して
+    /// 子のツリーを `pending` に積む。
+    async fn expand_tree(
+        &self,
+        owner: &str,
+        repo: &str,
+        (sha, prefix): PendingTree,
+        pending: &mut Vec<PendingTree>,
+    ) -> Result<Vec<TreeEntry>, GitHubError> {
+        let mut tree = self.fetch_tree(owner, repo, &sha, true).await?;
+        if tree.truncated {
+            debug!(target: "github_client", %sha, %prefix, "Subtree truncated; listing one level");
+            tree = self.fetch_tree(owner, repo, &sha, false).await?;
+            pending.extend(subtrees(&tree.tree, &prefix));
+        }
+        Ok(tree
+            .tree
+            .into_iter()
+            .map(|entry| TreeEntry {
+                path: format!("{}{}", prefix, entry.path),
+                ..entry
+            })
+            .collect())
+    }
+}
+
+/// 直下のサブツリーを `pending` に積む順（先頭のものが最後）で返す
+fn subtrees<'a>(
+    entries: &'a [TreeEntry],
+    prefix: &'a str,
+) -> impl Iterator<Item = PendingTree> + 'a {
+    entries
+        .iter()
+        .rev()
+        .filter(|e| e.entry_type == ObjectType::Tree)
+        .map(move |e| (e.sha.clone(), format!("{}{}/", prefix, e.path)))
+}
+
+/// ツリー以下のエントリを遅延して辿る
+///
+/// サブツリーは必要になった時点で取得される。`truncated` にならない大きさの
+/// サブツリーは1回のリクエストでまとめて取得する。
+pub struct TreeWalk<'a> {
+    client: &'a GitHubClient,
+    owner: String,
+    repo: String,
+    tree_sha: String,
+    globs: Vec<GlobMatcher>,
+}
+
+impl<'a> TreeWalk<'a> {
+    /// パスがパターンに一致するエントリだけを返す（複数指定した場合はいずれかに一致）
+    ///
+    /// `*` は `/` をまたがず、`**` は任意の階層に一致する。
+    pub fn glob(mut self, pattern: &str) -> Result<Self, GitHubError> {
+        let glob: Glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| {
+                GitHubError::InvalidRequestError(format!("Invalid glob {:?}: {}", pattern, e))
+            })?;
+        self.globs.push(glob.compile_matcher());
+        Ok(self)
+    }
+
+    /// エントリを順に返す `Stream` に変換する
+    pub fn into_stream(self) -> impl Stream<Item = Result<TreeEntry, GitHubError>> + 'a {
+        let pending = vec![(self.tree_sha.clone(), String::new())];
+        stream::try_unfold(
+            (self, pending, VecDeque::new()),
+            |(walk, mut pending, mut buffer)| async move {
+                loop {
+                    while let Some(entry) = buffer.pop_front() {
+                        if walk.matches(&entry) {
+                            return Ok(Some((entry, (walk, pending, buffer))));
+                        }
+                    }
+                    let Some(subtree) = pending.pop() else {
+                        return Ok(None);
+                    };
+                    buffer.extend(
+                        walk.client
+                            .expand_tree(&walk.owner, &walk.repo, subtree, &mut pending)
+                            .await?,
+                    );
+                }
+            },
+        )
+    }
+
+    fn matches(&self, entry: &TreeEntry) -> bool {
+        self.globs.is_empty() || self.globs.iter().any(|g| g.is_match(&entry.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use serde_json::json;
+
+    fn entry(path: &str, kind: &str, sha: &str) -> serde_json::Value {
+        let mode = if kind == "tree" { "040000" } else { "100644" };
+        json!({"path": path, "mode": mode, "type": kind, "sha": sha, "size": 10})
+    }
+
+    fn tree_body(sha: &str, entries: Vec<serde_json::Value>, truncated: bool) -> String {
+        json!({
+            "sha": sha,
+            "url": format!("https://api.github.com/repos/owner/repo/git/trees/{}", sha),
+            "tree": entries,
+            "truncated": truncated
+        })
+        .to_string()
+    }
+
+    /// ルートの再帰取得は `truncated`、`src` は再帰取得で完結するツリー
+    ///
+    /// 取得回数を確かめられるよう `root?recursive=1` のモックも返す。
+    async fn truncated_server() -> (mockito::ServerGuard, mockito::Mock) {
+        let mut server = mockito::Server::new_async().await;
+        let root = server
+            .mock("GET", "/repos/owner/repo/git/trees/root?recursive=1")
+            .with_status(200)
+            .with_body(tree_body(
+                "root",
+                vec![entry("README.md", "blob", "r1")],
+                true,
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/git/trees/root")
+            .with_status(200)
+            .with_body(tree_body(
+                "root",
+                vec![
+                    entry("README.md", "blob", "r1"),
+                    entry("docs", "tree", "d1"),
+                    entry("src", "tree", "s1"),
+                ],
+                false,
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/git/trees/d1?recursive=1")
+            .with_status(200)
+            .with_body(tree_body(
+                "d1",
+                vec![entry("guide.md", "blob", "g1")],
+                false,
+            ))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/git/trees/s1?recursive=1")
+            .with_status(200)
+            .with_body(tree_body(
+                "s1",
+                vec![
+                    entry("lib.rs", "blob", "l1"),
+                    entry("auth", "tree", "a1"),
+                    entry("auth/app.rs", "blob", "a2"),
+                ],
+                false,
+            ))
+            .create_async()
+            .await;
+        (server, root)
+    }
+
+    fn client(server: &mockito::ServerGuard) -> GitHubClient {
+        let mut client = GitHubClient::new("test_token".to_string());
+        client.base_url = server.url();
+        client
+    }
+
+    #[tokio::test]
+    async fn test_get_tree_recursive() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/repos/owner/repo/git/trees/root?recursive=1")
+            .with_status(200)
+            .with_body(tree_body(
+                "root",
+                vec![
+                    entry("src", "tree", "s1"),
+                    entry("src/lib.rs", "blob", "l1"),
+                ],
+                false,
+            ))
+            .create_async()
+            .await;
+
+        let tree = client(&server)
+            .get_tree("owner", "repo", "root", true)
+            .await
+            .unwrap();
+        assert_eq!(tree.tree.len(), 2);
+        assert_eq!(tree.tree[1].entry_type, ObjectType::Blob);
+        assert_eq!(tree.tree[1].size, Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_get_tree_walks_subtrees_when_truncated() {
+        let (server, root) = truncated_server().await;
+        let tree = client(&server)
+            .get_tree("owner", "repo", "root", true)
+            .await
+            .unwrap();
+
+        assert!(!tree.truncated);
+        let paths: Vec<_> = tree.tree.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "README.md",
+                "docs",
+                "src",
+                "docs/guide.md",
+                "src/lib.rs",
+                "src/auth",
+                "src/auth/app.rs"
+            ]
+        );
+        root.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_walk_tree_with_glob() {
+        let (server, _root) = truncated_server().await;
+        let client = client(&server);
+
+        let paths: Vec<String> = client
+            .walk_tree("owner", "repo", "root")
+            .glob("src/**/*.rs")
+            .unwrap()
+            .into_stream()
+            .map_ok(|e| e.path)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(paths, ["src/lib.rs", "src/auth/app.rs"]);
+
+        let top_level: Vec<String> = client
+            .walk_tree("owner", "repo", "root")
+            .glob("*.md")
+            .unwrap()
+            .into_stream()
+            .map_ok(|e| e.path)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(top_level, ["README.md"]);
+
+        assert!(client.walk_tree("owner", "repo", "root").glob("[").is_err());
+    }
+}